@@ -0,0 +1,118 @@
+//! Marching squares over the chunk corner grid.
+//!
+//! A chunk matrix has `size + 1` corners per side, and each cell between 4 corners gets an
+//! index from which corners are solid. The last row and column of corners are the same
+//! world positions as the first ones of the next chunk (both chunks generate and edit
+//! them), so the cells at a seam see the neighbour's corners without looking it up.
+
+use std::collections::HashMap;
+
+use macroquad::math::IVec2;
+
+use super::Matrix;
+
+pub const TOP_LEFT: u32 = 8;
+pub const TOP_RIGHT: u32 = 4;
+pub const BOTTOM_RIGHT: u32 = 2;
+pub const BOTTOM_LEFT: u32 = 1;
+
+pub const FULL: u32 = 15;
+
+/// Index (0..16) of the cell whose top left corner is `(x, y)`.
+pub fn marching_index(corners: &Matrix<u32>, x: usize, y: usize) -> u32 {
+    let solid = |x, y| corners[(x, y)] != 0;
+    let mut index = 0;
+    if solid(x, y) {
+        index |= TOP_LEFT;
+    }
+    if solid(x + 1, y) {
+        index |= TOP_RIGHT;
+    }
+    if solid(x + 1, y + 1) {
+        index |= BOTTOM_RIGHT;
+    }
+    if solid(x, y + 1) {
+        index |= BOTTOM_LEFT;
+    }
+    index
+}
+
+/// Cells that collide as a whole square. Saddles (two opposite corners) are kept
+/// connected, so they are filled too.
+pub fn is_full(index: u32) -> bool {
+    matches!(index, FULL | 5 | 10)
+}
+
+// Cell outline points, in cell units, with y going down
+const TL: (f32, f32) = (0.0, 0.0);
+const TR: (f32, f32) = (1.0, 0.0);
+const BR: (f32, f32) = (1.0, 1.0);
+const BL: (f32, f32) = (0.0, 1.0);
+const T: (f32, f32) = (0.5, 0.0);
+const R: (f32, f32) = (1.0, 0.5);
+const B: (f32, f32) = (0.5, 1.0);
+const L: (f32, f32) = (0.0, 0.5);
+
+/// The solid part of a partial cell, as a convex polygon in cell units.
+/// `None` for empty and full cells.
+pub fn slope_polygon(index: u32) -> Option<&'static [(f32, f32)]> {
+    let points: &'static [(f32, f32)] = match index {
+        1 => &[L, B, BL],
+        2 => &[B, R, BR],
+        4 => &[T, TR, R],
+        8 => &[TL, T, L],
+        3 => &[L, R, BR, BL],
+        6 => &[T, TR, BR, B],
+        12 => &[TL, TR, R, L],
+        9 => &[TL, T, B, BL],
+        7 => &[T, TR, BR, BL, L],
+        11 => &[TL, T, R, BR, BL],
+        13 => &[TL, TR, R, B, BL],
+        14 => &[TL, TR, BR, B, L],
+        _ => return None,
+    };
+    Some(points)
+}
+
+/// Where a rock block is drawn in a tileset: the corners of the block give the outer
+/// corners and borders, the tile after the top left corner gives the plane. Inner
+/// corners are named by the corner that is water.
+pub struct AutotileLayout {
+    pub block_min: IVec2,
+    pub block_max: IVec2,
+    pub inner_top_left: IVec2,
+    pub inner_top_right: IVec2,
+    pub inner_bottom_right: IVec2,
+    pub inner_bottom_left: IVec2,
+}
+
+impl AutotileLayout {
+    /// Tileset position for each marching squares index, for `TileMap::tiles`.
+    pub fn tiles(&self) -> HashMap<u32, IVec2> {
+        let (min, max) = (self.block_min, self.block_max);
+        let plane = min + IVec2::ONE;
+
+        let mut cu = HashMap::new();
+        cu.insert(0, IVec2::ZERO);
+        //outer corners
+        cu.insert(BOTTOM_RIGHT, min);
+        cu.insert(BOTTOM_LEFT, IVec2::new(max.x, min.y));
+        cu.insert(TOP_RIGHT, IVec2::new(min.x, max.y));
+        cu.insert(TOP_LEFT, max);
+        //borders
+        cu.insert(BOTTOM_LEFT | BOTTOM_RIGHT, IVec2::new(plane.x, min.y));
+        cu.insert(TOP_LEFT | TOP_RIGHT, IVec2::new(plane.x, max.y));
+        cu.insert(TOP_RIGHT | BOTTOM_RIGHT, IVec2::new(min.x, plane.y));
+        cu.insert(TOP_LEFT | BOTTOM_LEFT, IVec2::new(max.x, plane.y));
+        //inner corners
+        cu.insert(FULL & !TOP_LEFT, self.inner_top_left);
+        cu.insert(FULL & !TOP_RIGHT, self.inner_top_right);
+        cu.insert(FULL & !BOTTOM_RIGHT, self.inner_bottom_right);
+        cu.insert(FULL & !BOTTOM_LEFT, self.inner_bottom_left);
+        //plane (and saddles, which are filled)
+        cu.insert(FULL, plane);
+        cu.insert(TOP_RIGHT | BOTTOM_LEFT, plane);
+        cu.insert(TOP_LEFT | BOTTOM_RIGHT, plane);
+        cu
+    }
+}
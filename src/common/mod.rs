@@ -1,5 +1,9 @@
 use std::ops::{Index, IndexMut};
 
+pub mod autotile;
+pub mod rng;
+pub mod shapes;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Matrix<T> {
     pub data: Vec<T>,
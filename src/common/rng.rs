@@ -0,0 +1,36 @@
+use macroquad::{math::IVec2, rand::RandGenerator};
+
+/// Streams split the randomness of a chunk by purpose, so adding a new kind of roll
+/// (e.g. monsters) doesn't shift the results of the older ones (e.g. items).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    Items,
+    Rooms,
+}
+
+/// SplitMix64 finalizer, good enough to spread close inputs (neighbour chunks) apart.
+pub fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Hashes the world seed with a chunk position and a stream into a new seed.
+pub fn chunk_seed(seed: u64, chunk: IVec2, stream: RngStream) -> u64 {
+    let pos = ((chunk.x as u32 as u64) << 32) | chunk.y as u32 as u64;
+    mix64(mix64(seed ^ mix64(pos)) ^ stream as u64)
+}
+
+/// A generator that only depends on `(seed, chunk, stream)`, never on the order
+/// in which chunks were loaded.
+pub fn chunk_rng(seed: u64, chunk: IVec2, stream: RngStream) -> RandGenerator {
+    let rng = RandGenerator::new();
+    rng.srand(chunk_seed(seed, chunk, stream));
+    rng
+}
+
+/// FastNoiseLite only takes an i32 seed, so fold the 64 bits into it.
+pub fn noise_seed(seed: u64) -> i32 {
+    (seed ^ (seed >> 32)) as i32
+}
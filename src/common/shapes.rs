@@ -0,0 +1,279 @@
+//! Collision shapes for the solid cells of a chunk corner matrix (see `autotile`).
+
+use std::{collections::HashMap, ops::Range};
+
+use macroquad::math::{IVec2, Vec2, ivec2};
+
+use super::{
+    Matrix,
+    autotile::{self, marching_index},
+};
+
+/// A rectangle of full cells, in cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellRect {
+    pub x: usize,
+    pub y: usize,
+    pub w: usize,
+    pub h: usize,
+}
+
+/// Covers the full cells of the cell rows `rows` with as few rectangles as the greedy
+/// way finds: grow right as far as possible, then down while the whole span stays full.
+pub fn greedy_rects(corners: &Matrix<u32>, rows: Range<usize>) -> Vec<CellRect> {
+    let offset = rows.start;
+    greedy_rects_where(corners.width - 1, rows.len(), |x, y| {
+        autotile::is_full(marching_index(corners, x, y + offset))
+    })
+    .into_iter()
+    .map(|rect| CellRect {
+        y: rect.y + offset,
+        ..rect
+    })
+    .collect()
+}
+
+/// `greedy_rects` over any grid, `full` telling which cells to cover.
+pub fn greedy_rects_where(
+    width: usize,
+    height: usize,
+    full: impl Fn(usize, usize) -> bool,
+) -> Vec<CellRect> {
+    let mut taken = Matrix::new(width, height, false);
+    let free = |taken: &Matrix<bool>, x, y| !taken[(x, y)] && full(x, y);
+
+    let mut rects = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !free(&taken, x, y) {
+                continue;
+            }
+
+            let mut w = 1;
+            while x + w < width && free(&taken, x + w, y) {
+                w += 1;
+            }
+            let mut h = 1;
+            while y + h < height && (x..x + w).all(|cx| free(&taken, cx, y + h)) {
+                h += 1;
+            }
+
+            for ry in y..y + h {
+                for rx in x..x + w {
+                    taken[(rx, ry)] = true;
+                }
+            }
+            rects.push(CellRect { x, y, w, h });
+        }
+    }
+    rects
+}
+
+// Edge midpoints of a cell, in half cells, with y going down
+const T: IVec2 = ivec2(1, 0);
+const R: IVec2 = ivec2(2, 1);
+const B: IVec2 = ivec2(1, 2);
+const L: IVec2 = ivec2(0, 1);
+
+/// Border segments inside a cell. Saddles keep the solid corners connected, as in
+/// `autotile::is_full`.
+fn cell_segments(index: u32) -> &'static [(IVec2, IVec2)] {
+    match index {
+        1 | 14 => &[(L, B)],
+        2 | 13 => &[(B, R)],
+        3 | 12 => &[(L, R)],
+        4 | 11 => &[(T, R)],
+        6 | 9 => &[(T, B)],
+        7 | 8 => &[(T, L)],
+        5 => &[(T, L), (B, R)],
+        10 => &[(T, R), (L, B)],
+        _ => &[],
+    }
+}
+
+/// Outline of the solid region as polylines, in cell units. Loops end on their first
+/// point; lines that leave the chunk stop at its border, where the neighbour's go on.
+pub fn contours(corners: &Matrix<u32>) -> Vec<Vec<Vec2>> {
+    let mut segments = Vec::new();
+    for y in 0..corners.height - 1 {
+        for x in 0..corners.width - 1 {
+            let origin = ivec2(x as i32, y as i32) * 2;
+            for &(a, b) in cell_segments(marching_index(corners, x, y)) {
+                segments.push((origin + a, origin + b));
+            }
+        }
+    }
+
+    let mut at_point: HashMap<IVec2, Vec<usize>> = HashMap::new();
+    for (i, &(a, b)) in segments.iter().enumerate() {
+        at_point.entry(a).or_default().push(i);
+        at_point.entry(b).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+    // Open lines first, starting at their ends, then whatever is left are loops
+    let mut starts: Vec<usize> = (0..segments.len()).collect();
+    starts.sort_by_key(|&i| {
+        let (a, b) = segments[i];
+        (at_point[&a].len() == 2 && at_point[&b].len() == 2) as u8
+    });
+    for start in starts {
+        if used[start] {
+            continue;
+        }
+        let (a, b) = segments[start];
+        let first = if at_point[&a].len() == 1 { a } else { b };
+        let mut line = vec![first];
+        let mut point = first;
+        let mut current = Some(start);
+
+        while let Some(i) = current {
+            used[i] = true;
+            let (a, b) = segments[i];
+            point = if a == point { b } else { a };
+            line.push(point);
+            current = at_point[&point].iter().copied().find(|&j| !used[j]);
+        }
+        lines.push(simplify(line));
+    }
+
+    lines
+        .into_iter()
+        .map(|line| line.iter().map(|p| p.as_vec2() / 2.0).collect())
+        .collect()
+}
+
+/// Drops the points in the middle of straight runs.
+fn simplify(line: Vec<IVec2>) -> Vec<IVec2> {
+    let mut out: Vec<IVec2> = Vec::with_capacity(line.len());
+    for p in line {
+        if out.len() >= 2 {
+            let (a, b) = (out[out.len() - 2], out[out.len() - 1]);
+            if (b - a).perp_dot(p - b) == 0 {
+                out.pop();
+            }
+        }
+        out.push(p);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Corners from rows of `#` (rock) and `.` (water).
+    fn corners(rows: &[&str]) -> Matrix<u32> {
+        let mut matrix = Matrix::new(rows[0].len(), rows.len(), 0);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                matrix[(x, y)] = (c == '#') as u32;
+            }
+        }
+        matrix
+    }
+
+    #[test]
+    fn greedy_rects_merge_a_block() {
+        let m = corners(&[
+            "###.", //
+            "###.", "###.", "....",
+        ]);
+        assert_eq!(
+            greedy_rects(&m, 0..3),
+            vec![CellRect {
+                x: 0,
+                y: 0,
+                w: 2,
+                h: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn greedy_rects_cover_only_the_rows_asked() {
+        let m = corners(&[
+            "###", //
+            "###", "###", "###",
+        ]);
+        assert_eq!(
+            greedy_rects(&m, 1..3),
+            vec![CellRect {
+                x: 0,
+                y: 1,
+                w: 2,
+                h: 2
+            }]
+        );
+        assert!(greedy_rects(&m, 2..2).is_empty());
+    }
+
+    #[test]
+    fn greedy_rects_where_covers_every_full_cell_once() {
+        let full = |x: usize, y: usize| !(x + y).is_multiple_of(3);
+        let rects = greedy_rects_where(5, 4, full);
+
+        let mut covered = Matrix::new(5, 4, 0);
+        for rect in rects {
+            for y in rect.y..rect.y + rect.h {
+                for x in rect.x..rect.x + rect.w {
+                    covered[(x, y)] += 1;
+                }
+            }
+        }
+        for y in 0..4 {
+            for x in 0..5 {
+                assert_eq!(covered[(x, y)], full(x, y) as u32, "célula ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn contours_of_solid_or_empty_chunks() {
+        assert!(contours(&corners(&["###", "###", "###"])).is_empty());
+        assert!(contours(&corners(&["...", "...", "..."])).is_empty());
+    }
+
+    #[test]
+    fn contour_around_a_rock_is_a_loop() {
+        let m = corners(&[
+            ".....", //
+            ".....", "..#..", ".....", ".....",
+        ]);
+        let lines = contours(&m);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.first(), line.last());
+        // A diamond around the corner (2, 2)
+        assert_eq!(line.len(), 5);
+        assert!(line.iter().all(|p| p.distance(Vec2::new(2.0, 2.0)) == 0.5));
+    }
+
+    #[test]
+    fn contour_leaving_the_chunk_stops_at_the_border() {
+        let m = corners(&[
+            "#...", //
+            "#...", "#...",
+        ]);
+        let lines = contours(&m);
+        assert_eq!(lines.len(), 1);
+        let mut ends = [lines[0][0], lines[0][lines[0].len() - 1]];
+        ends.sort_by(|a, b| a.y.total_cmp(&b.y));
+        assert_eq!(lines[0].len(), 2);
+        assert_eq!(ends, [Vec2::new(0.5, 0.0), Vec2::new(0.5, 2.0)]);
+    }
+
+    #[test]
+    fn simplify_drops_points_on_straight_runs() {
+        let line = vec![
+            ivec2(0, 0),
+            ivec2(1, 0),
+            ivec2(2, 0),
+            ivec2(2, 1),
+            ivec2(2, 2),
+        ];
+        assert_eq!(simplify(line), vec![ivec2(0, 0), ivec2(2, 0), ivec2(2, 2)]);
+        assert_eq!(simplify(vec![ivec2(0, 0)]), vec![ivec2(0, 0)]);
+    }
+}
@@ -0,0 +1,226 @@
+use legion::{Entity, systems::CommandBuffer};
+use macroquad::{
+    color::Color,
+    math::{IVec2, Vec2},
+    rand::gen_range,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Body, CollisionLayer, Damage, DebugSprite, Faction, Health, Loot, Monster, Transform};
+
+/// One thing a monster may want to do. Distances are in meters; points are offsets from
+/// the monster's home.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Behaviour {
+    /// Drifts between random points within `radius` of home.
+    Wander { radius: f32 },
+    /// Swims through `points` in a loop.
+    Patrol { points: Vec<[f32; 2]> },
+    /// Swims at the player while closer than `range`.
+    Chase { range: f32 },
+    /// Swims away from the player while closer than `range`.
+    Flee { range: f32 },
+    /// Goes back home once farther than `leash`, and keeps going until near it.
+    ReturnHome { leash: f32 },
+}
+
+/// What a brain knows each frame.
+pub struct Senses<'a> {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub player: Option<Vec2>,
+    /// Where to head for to reach a point `(from, to)` through the caves, e.g.
+    /// `PathCache::next_waypoint`.
+    pub route: &'a mut dyn FnMut(Vec2, Vec2) -> Vec2,
+}
+
+/// A priority selector over behaviours: the first one that wants to act drives the
+/// monster, the others wait. Steering asks for a velocity, and the difference to the
+/// current one becomes the force.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Brain {
+    pub behaviours: Vec<Behaviour>,
+    pub home: Vec2,
+    pub max_speed: f32,
+    /// In m/s², so the same brain steers light and heavy bodies alike.
+    pub max_acceleration: f32,
+    /// Index of the behaviour that acted last frame.
+    pub current: Option<usize>,
+    target: Option<Vec2>,
+    patrol_index: usize,
+}
+
+/// Near enough to a waypoint to take the next one.
+const ARRIVE_DISTANCE: f32 = 0.1;
+
+impl Brain {
+    /// A brain around `home`, with the default behaviours if `behaviours` is empty.
+    pub fn new(home: Vec2, behaviours: Vec<Behaviour>) -> Self {
+        let behaviours = if behaviours.is_empty() {
+            Self::default_behaviours()
+        } else {
+            behaviours
+        };
+        Self {
+            behaviours,
+            home,
+            max_speed: 0.8,
+            max_acceleration: 3.0,
+            current: None,
+            target: None,
+            patrol_index: 0,
+        }
+    }
+
+    /// Stays around home and goes for the player when close, without being dragged
+    /// too far.
+    pub fn default_behaviours() -> Vec<Behaviour> {
+        vec![
+            Behaviour::ReturnHome { leash: 3.0 },
+            Behaviour::Chase { range: 1.5 },
+            Behaviour::Wander { radius: 1.0 },
+        ]
+    }
+
+    /// The acceleration to apply this frame.
+    pub fn steer(&mut self, senses: &mut Senses) -> Vec2 {
+        let mut chosen = None;
+        for i in 0..self.behaviours.len() {
+            if let Some(desired) = self.desired_velocity(i, senses) {
+                chosen = Some((i, desired));
+                break;
+            }
+        }
+        if chosen.map(|(i, _)| i) != self.current {
+            self.target = None;
+        }
+        self.current = chosen.map(|(i, _)| i);
+
+        // Nothing to do: brake
+        let desired = chosen.map_or(Vec2::ZERO, |(_, desired)| desired);
+        (desired - senses.velocity).clamp_length_max(self.max_acceleration)
+    }
+
+    //=====PRIVATE======
+    fn desired_velocity(&mut self, index: usize, senses: &mut Senses) -> Option<Vec2> {
+        let pos = senses.position;
+        match &self.behaviours[index] {
+            Behaviour::Wander { radius } => {
+                let radius = *radius;
+                let target = match self.target {
+                    Some(target) if target.distance(pos) > ARRIVE_DISTANCE => target,
+                    _ => {
+                        let angle = gen_range(0.0, std::f32::consts::TAU);
+                        self.home + Vec2::from_angle(angle) * gen_range(0.0, radius)
+                    }
+                };
+                self.target = Some(target);
+                Some(self.seek(senses, target) * 0.5)
+            }
+            Behaviour::Patrol { points } => {
+                if points.is_empty() {
+                    return None;
+                }
+                let mut target =
+                    self.home + Vec2::from_array(points[self.patrol_index % points.len()]);
+                if target.distance(pos) < ARRIVE_DISTANCE {
+                    self.patrol_index = (self.patrol_index + 1) % points.len();
+                    target = self.home + Vec2::from_array(points[self.patrol_index]);
+                }
+                Some(self.seek(senses, target))
+            }
+            Behaviour::Chase { range } => {
+                let player = senses.player?;
+                (player.distance(pos) < *range).then(|| self.seek(senses, player))
+            }
+            Behaviour::Flee { range } => {
+                let player = senses.player?;
+                (player.distance(pos) < *range)
+                    .then(|| (pos - player).normalize_or_zero() * self.max_speed)
+            }
+            Behaviour::ReturnHome { leash } => {
+                let distance = self.home.distance(pos);
+                // Once going home, only stop when close, or it would turn back at the leash
+                let going = self.current == Some(index) && distance > leash * 0.25;
+                (going || distance > *leash).then(|| self.seek(senses, self.home))
+            }
+        }
+    }
+
+    /// Full speed towards `target` along the route, slowing down on arrival.
+    fn seek(&self, senses: &mut Senses, target: Vec2) -> Vec2 {
+        let pos = senses.position;
+        let waypoint = (senses.route)(pos, target);
+        let to_waypoint = waypoint - pos;
+        let slowing = if waypoint == target {
+            (to_waypoint.length() / 0.5).min(1.0)
+        } else {
+            1.0
+        };
+        to_waypoint.normalize_or_zero() * self.max_speed * slowing
+    }
+}
+
+impl Monster {
+    /// Pushes a monster with its body, a placeholder sprite, its brain, its bite and
+    /// what it drops on death. It starts with `health`, or full health if `None`.
+    pub fn push(
+        cb: &mut CommandBuffer,
+        position: Vec2,
+        brain: Brain,
+        loot: Loot,
+        health: Option<f32>,
+        chunk: IVec2,
+    ) -> Entity {
+        cb.push(Self::bundle(position, brain, loot, health, chunk))
+    }
+
+    /// See `WorldItem::bundle`.
+    pub fn bundle(
+        position: Vec2,
+        brain: Brain,
+        loot: Loot,
+        health: Option<f32>,
+        chunk: IVec2,
+    ) -> (
+        Transform,
+        Body,
+        DebugSprite,
+        Brain,
+        Health,
+        Damage,
+        Loot,
+        Monster,
+    ) {
+        let mut life = Health::new(30.0, Faction::Monster);
+        if let Some(current) = health {
+            life.current = current.min(life.max);
+        }
+        (
+            Transform {
+                position,
+                ..Default::default()
+            },
+            Body::new(Vec2::new(0.12, 0.12), true)
+                .with_layer(CollisionLayer::Monster)
+                .with_buoyancy(1.0),
+            DebugSprite {
+                size: Vec2::new(0.24, 0.24),
+                color: Color::new(0.8, 0.2, 0.3, 1.0),
+                z_order: 1.0,
+            },
+            brain,
+            life,
+            Damage {
+                amount: 10.0,
+                faction: Faction::Monster,
+                consumed_on_hit: false,
+            },
+            loot,
+            Monster {
+                active: true,
+                chunk,
+            },
+        )
+    }
+}
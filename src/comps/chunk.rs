@@ -1,21 +1,34 @@
-use legion::{Entity, query::*, systems::CommandBuffer, world::SubWorld};
-use macroquad::math::{IVec2, Rect, UVec2, Vec2, uvec2, vec2};
-use nalgebra::vector;
+use std::ops::{Range, RangeInclusive};
+
+use legion::{Entity, EntityStore, query::*, systems::CommandBuffer, world::SubWorld};
+use macroquad::math::{IVec2, Rect, UVec2, Vec2, vec2};
+use nalgebra::{point, vector};
 use rapier2d::prelude::{
     Collider, ColliderBuilder, ColliderHandle, RigidBody, RigidBodyBuilder, RigidBodyHandle,
 };
 
 use crate::{
-    common::Matrix,
+    common::{
+        Matrix,
+        autotile::{self, marching_index},
+        rng::RngStream,
+        shapes::{self, CellRect},
+    },
     resources::{
-        chunk_manager::ChunkManager,
+        chunk_manager::{ChunkManager, ColliderMode},
+        chunk_store::{ChunkStore, EntityRecord},
+        inventory::ItemDef,
         physics::PhysicsContext,
-        weapons::{SurfaceType, surface_type_to_bit},
+        rooms::RoomObject,
+        spawn_table::SpawnTemplate,
     },
     sys::chunk::get_world_position_by_chunk,
 };
 
-use super::Monster;
+use super::{
+    AirSource, Body, Brain, CollisionLayer, Fish, Health, Loot, Monster, SpawnPoint, Sprite,
+    Transform, WorldItem,
+};
 
 pub fn calculate_tile_position(
     chunk_pos: IVec2,
@@ -43,6 +56,8 @@ pub struct Chunk {
     pub state: ChunkState,
     /// in meters
     pub rect: Rect,
+    /// Goes up each time a tile is edited, so its `ChunkBake` knows when to be redrawn.
+    pub revision: u32,
 }
 
 impl Chunk {
@@ -52,22 +67,40 @@ impl Chunk {
             matrix: None,
             state: ChunkState::Freed,
             rect,
+            revision: 0,
         }
     }
 
-    pub fn load(&self, e: &Entity, world: &SubWorld, cm: &ChunkManager, cb: &mut CommandBuffer) {
+    /// Takes the generated matrix (see `ChunkJobs`), applies the saved edits over it and
+    /// wakes or spawns the chunk entities.
+    pub fn load(
+        &self,
+        e: &Entity,
+        world: &SubWorld,
+        cm: &ChunkManager,
+        store: &mut ChunkStore,
+        mut matrix: Matrix<u32>,
+        cb: &mut CommandBuffer,
+    ) {
         if self.state == ChunkState::Loaded {
             return;
         }
 
-        let matrix = self.gen_matrix(cm);
+        store.apply_tiles(self.pos, &mut matrix);
 
         if self.state == ChunkState::Unloaded {
             self.set_inchunk_monsters_active(world, cb, true);
             self.set_inchunk_items_active(world, cb, true);
+            self.set_inchunk_fish_active(world, cb, true);
         }
         if self.state == ChunkState::Freed {
-            self.spawn(&matrix, cm, cb);
+            match store.get(self.pos).and_then(|d| d.entities.as_ref()) {
+                Some(records) => self.respawn(records, cb),
+                None => {
+                    self.spawn(&matrix, cm, cb);
+                    self.spawn_room_objects(cm, cb);
+                }
+            }
         }
 
         cb.add_component(
@@ -86,6 +119,7 @@ impl Chunk {
         }
         self.set_inchunk_monsters_active(world, cb, false);
         self.set_inchunk_items_active(world, cb, false);
+        self.set_inchunk_fish_active(world, cb, false);
         cb.add_component(
             *e,
             Chunk {
@@ -96,12 +130,113 @@ impl Chunk {
         );
     }
 
-    pub fn free(&self, e: &Entity, world: &SubWorld, cb: &mut CommandBuffer) {
-        self.destroy_inchunk_monsters(world, cb);
-        self.destroy_inchunk_items(world, cb);
+    pub fn free(
+        &self,
+        e: &Entity,
+        world: &SubWorld,
+        store: &mut ChunkStore,
+        pc: &mut PhysicsContext,
+        cb: &mut CommandBuffer,
+    ) {
+        store.record_entities(self.pos, self.to_records(world));
+        self.destroy_inchunk_monsters(world, pc, cb);
+        self.destroy_inchunk_items(world, pc, cb);
+        self.destroy_inchunk_spawn_points(world, cb);
+        self.destroy_inchunk_fish(world, cb);
+        self.destroy_inchunk_air(world, pc, cb);
         cb.remove(*e);
     }
 
+    /// Changes a tile of the loaded matrix. Returns false if the chunk isn't loaded, the
+    /// tile is out of it or it already had that value.
+    pub fn set_tile(&mut self, tile: UVec2, value: u32) -> bool {
+        let Some(matrix) = self.matrix.as_mut() else {
+            // The store applies it on load, but the bake is already outdated
+            self.revision += 1;
+            return false;
+        };
+        let Some(old) = matrix.get_mut(tile.x as usize, tile.y as usize) else {
+            return false;
+        };
+        if *old == value {
+            return false;
+        }
+        *old = value;
+        self.revision += 1;
+        true
+    }
+
+    /// The entities of the chunk, as the store keeps them (see
+    /// `ChunkStore::record_entities`).
+    pub fn to_records(&self, world: &impl EntityStore) -> Vec<EntityRecord> {
+        let mut records = Vec::new();
+
+        let mut q = <(&Transform, &WorldItem, Option<&Sprite>)>::query();
+        for (t, i, spr) in q.iter(world) {
+            if i.chunk == self.pos {
+                records.push(EntityRecord::Item {
+                    position: t.position.to_array(),
+                    item: i.item.clone(),
+                    quantity: i.quantity,
+                    image_path: spr.map(|s| s.image_path.clone()).unwrap_or_default(),
+                });
+            }
+        }
+
+        let mut q = <(
+            &Transform,
+            &Monster,
+            Option<&Brain>,
+            Option<&Loot>,
+            Option<&Health>,
+        )>::query();
+        for (t, m, brain, loot, health) in q.iter(world) {
+            if m.chunk == self.pos {
+                records.push(EntityRecord::Monster {
+                    position: t.position.to_array(),
+                    home: brain.map(|b| b.home.to_array()),
+                    behaviours: brain.map(|b| b.behaviours.clone()).unwrap_or_default(),
+                    loot: loot.map(|l| l.drops.clone()).unwrap_or_default(),
+                    health: health.map(|h| h.current),
+                });
+            }
+        }
+
+        let mut q = <(&Transform, &Fish)>::query();
+        for (t, f) in q.iter(world) {
+            if f.chunk == self.pos {
+                records.push(EntityRecord::Fish {
+                    position: t.position.to_array(),
+                    velocity: f.velocity.to_array(),
+                    school: f.school,
+                });
+            }
+        }
+
+        let mut q = <(&Transform, &AirSource)>::query();
+        for (t, air) in q.iter(world) {
+            if air.chunk == self.pos {
+                records.push(EntityRecord::Air {
+                    position: t.position.to_array(),
+                    radius: air.radius,
+                    rate: air.rate,
+                });
+            }
+        }
+
+        let mut q = <(&Transform, &SpawnPoint)>::query();
+        for (t, s) in q.iter(world) {
+            if s.chunk == self.pos {
+                records.push(EntityRecord::SpawnPoint {
+                    position: t.position.to_array(),
+                    kind: s.kind.clone(),
+                });
+            }
+        }
+
+        records
+    }
+
     //=====PRIVATE======
     fn set_inchunk_monsters_active(&self, world: &SubWorld, cb: &mut CommandBuffer, active: bool) {
         let mut q = <(Entity, &Monster)>::query();
@@ -115,41 +250,23 @@ impl Chunk {
         }
     }
 
-    fn gen_matrix(&self, cm: &ChunkManager) -> Matrix<u32> {
-        let mut matrix_buffer: Matrix<u32> = Matrix::new(
-            (cm.chunk_size_in_tiles.x + 1) as usize,
-            (cm.chunk_size_in_tiles.y + 1) as usize,
-            0,
-        );
-
-        for y in 0..=cm.chunk_size_in_tiles.y as usize {
-            for x in 0..=cm.chunk_size_in_tiles.x as usize {
-                let world_pos = calculate_tile_position(
-                    self.pos,
-                    uvec2(x as u32, y as u32),
-                    cm.chunk_size_in_tiles,
-                    cm.tile_size_in_meters,
-                );
-                let world_x: f32 = world_pos.x * cm.noise_scale.x;
-                let world_y: f32 = world_pos.y * cm.noise_scale.y;
-                let noise_val = cm.world_noise.get_noise_2d(world_x, world_y);
-                matrix_buffer[(x, y)] = if noise_val < cm.threshold { 0 } else { 1 };
-            }
-        }
-        matrix_buffer
-    }
-
-    fn destroy_inchunk_monsters(&self, world: &SubWorld, cb: &mut CommandBuffer) {
-        let mut q = <(Entity, &Monster)>::query();
-        for (e, m) in q.iter(world) {
+    fn destroy_inchunk_monsters(
+        &self,
+        world: &SubWorld,
+        pc: &mut PhysicsContext,
+        cb: &mut CommandBuffer,
+    ) {
+        let mut q = <(Entity, &Monster, Option<&Body>)>::query();
+        for (e, m, body) in q.iter(world) {
             if m.chunk == self.pos {
+                Self::remove_body(body, pc);
                 cb.remove(*e);
             }
         }
     }
 
     fn set_inchunk_items_active(&self, world: &SubWorld, cb: &mut CommandBuffer, active: bool) {
-        let mut q = <(Entity, &crate::comps::WorldItem)>::query();
+        let mut q = <(Entity, &WorldItem)>::query();
         for (entity, item) in q.iter(world) {
             if item.chunk == self.pos {
                 let mut new_item = item.clone();
@@ -159,58 +276,283 @@ impl Chunk {
         }
     }
 
-    fn destroy_inchunk_items(&self, world: &SubWorld, cb: &mut CommandBuffer) {
-        let mut q = <(Entity, &crate::comps::WorldItem)>::query();
-        for (e, i) in q.iter(world) {
+    fn destroy_inchunk_items(
+        &self,
+        world: &SubWorld,
+        pc: &mut PhysicsContext,
+        cb: &mut CommandBuffer,
+    ) {
+        let mut q = <(Entity, &WorldItem, Option<&Body>)>::query();
+        for (e, i, body) in q.iter(world) {
             if i.chunk == self.pos {
+                Self::remove_body(body, pc);
                 cb.remove(*e);
             }
         }
     }
 
+    fn destroy_inchunk_spawn_points(&self, world: &SubWorld, cb: &mut CommandBuffer) {
+        let mut q = <(Entity, &SpawnPoint)>::query();
+        for (e, s) in q.iter(world) {
+            if s.chunk == self.pos {
+                cb.remove(*e);
+            }
+        }
+    }
+
+    fn set_inchunk_fish_active(&self, world: &SubWorld, cb: &mut CommandBuffer, active: bool) {
+        let mut q = <(Entity, &Fish)>::query();
+        for (entity, fish) in q.iter(world) {
+            if fish.chunk == self.pos {
+                cb.add_component(
+                    *entity,
+                    Fish {
+                        active,
+                        ..fish.clone()
+                    },
+                );
+            }
+        }
+    }
+
+    fn destroy_inchunk_fish(&self, world: &SubWorld, cb: &mut CommandBuffer) {
+        let mut q = <(Entity, &Fish)>::query();
+        for (e, f) in q.iter(world) {
+            if f.chunk == self.pos {
+                cb.remove(*e);
+            }
+        }
+    }
+
+    fn destroy_inchunk_air(
+        &self,
+        world: &SubWorld,
+        pc: &mut PhysicsContext,
+        cb: &mut CommandBuffer,
+    ) {
+        let mut q = <(Entity, &AirSource, Option<&Body>)>::query();
+        for (e, air, body) in q.iter(world) {
+            if air.chunk == self.pos {
+                Self::remove_body(body, pc);
+                cb.remove(*e);
+            }
+        }
+    }
+
+    fn remove_body(body: Option<&Body>, pc: &mut PhysicsContext) {
+        if let Some(handle) = body.and_then(|b| b.body_handle) {
+            pc.bodies.remove(
+                handle,
+                &mut pc.islands,
+                &mut pc.colliders,
+                &mut pc.impulse_joints,
+                &mut pc.multibody_joints,
+                true,
+            );
+        }
+    }
+
+    fn respawn(&self, records: &[EntityRecord], cb: &mut CommandBuffer) {
+        for record in records {
+            match record {
+                EntityRecord::Item {
+                    position,
+                    item,
+                    quantity,
+                    image_path,
+                } => {
+                    WorldItem::push(
+                        cb,
+                        Vec2::from_array(*position),
+                        item.clone(),
+                        *quantity,
+                        image_path.clone(),
+                        self.pos,
+                    );
+                }
+                EntityRecord::Monster {
+                    position,
+                    home,
+                    behaviours,
+                    loot,
+                    health,
+                } => {
+                    let position = Vec2::from_array(*position);
+                    let home = home.map_or(position, Vec2::from_array);
+                    let brain = Brain::new(home, behaviours.clone());
+                    let loot = Loot {
+                        drops: loot.clone(),
+                    };
+                    Monster::push(cb, position, brain, loot, *health, self.pos);
+                }
+                EntityRecord::Air {
+                    position,
+                    radius,
+                    rate,
+                } => {
+                    AirSource::push(cb, Vec2::from_array(*position), *radius, *rate, self.pos);
+                }
+                EntityRecord::SpawnPoint { position, kind } => {
+                    self.push_spawn_point(Vec2::from_array(*position), kind.clone(), cb);
+                }
+                EntityRecord::Fish {
+                    position,
+                    velocity,
+                    school,
+                } => {
+                    Fish::push(
+                        cb,
+                        Vec2::from_array(*position),
+                        Vec2::from_array(*velocity),
+                        *school,
+                        self.pos,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Rolls the spawn tables (see `SpawnTable`) over the water tiles of the chunk.
     fn spawn(&self, matrix: &Matrix<u32>, cm: &ChunkManager, cb: &mut CommandBuffer) {
-        for y in 0..matrix.height {
-            for x in 0..matrix.width {
-                if matrix[(x, y)] == 0 {
-                    if macroquad::rand::gen_range(0.0f32, 1.0f32) < 0.0005 {
-                        let tile_pos = UVec2::new(x as u32, y as u32);
-                        let world_pos = calculate_tile_position(
-                            self.pos,
-                            tile_pos,
-                            cm.chunk_size_in_tiles,
-                            cm.tile_size_in_meters,
-                        );
-
-                        cb.push((
-                            crate::comps::Transform {
-                                position: world_pos,
-                                ..Default::default()
-                            },
-                            crate::comps::Body::new(Vec2::new(0.1, 0.1), true),
-                            crate::comps::Sprite {
-                                image_path: "assets/gun.png".into(),
-                                z_order: 1.0,
-                                flip_x: false,
-                                flip_y: false,
-                            },
-                            crate::comps::WorldItem {
-                                item: crate::resources::inventory::ItemDef {
-                                    name: "Arma Debug".into(),
-                                    data: vec![],
-                                    use_func: Some(|_cmd, _context| {
-                                        println!("Usando arma debug!");
-                                    }),
-                                },
-                                quantity: 1,
-                                chunk: self.pos,
-                                active: true,
-                            },
-                        ));
+        let rng = cm.chunk_rng(self.pos, RngStream::Items);
+        for table in &cm.spawns.tables {
+            let mut spawned = 0;
+            for _ in 0..table.attempts {
+                if spawned >= table.cap {
+                    break;
+                }
+                // The chunk's own rng, drawn in a fixed order: a chunk always rolls the same
+                // spawns
+                let x = rng.gen_range(1, matrix.width - 1);
+                let y = rng.gen_range(1, matrix.height - 1);
+                if rng.gen_range(0.0f32, 1.0f32) >= table.chance || matrix[(x, y)] != 0 {
+                    continue;
+                }
+
+                let world_pos = calculate_tile_position(
+                    self.pos,
+                    UVec2::new(x as u32, y as u32),
+                    cm.chunk_size_in_tiles,
+                    cm.tile_size_in_meters,
+                );
+                let depth = world_pos.y / cm.chunk_size_in_meters.y;
+                let biome = &cm.biomes.dominant(world_pos).name;
+                let Some(entry) = table.pick(&rng, |e| {
+                    e.allows(depth, biome) && e.placement.fits(matrix, x, y)
+                }) else {
+                    continue;
+                };
+
+                match &entry.spawn {
+                    SpawnTemplate::Item {
+                        item,
+                        image_path,
+                        quantity,
+                    } => match ItemDef::by_name(item) {
+                        Some(def) => {
+                            WorldItem::push(
+                                cb,
+                                world_pos,
+                                def,
+                                *quantity,
+                                image_path.clone(),
+                                self.pos,
+                            );
+                        }
+                        None => {
+                            eprintln!("Item desconhecido na tabela {}: {}", table.name, item);
+                            continue;
+                        }
+                    },
+                    SpawnTemplate::Monster { behaviours, loot } => {
+                        let brain = Brain::new(world_pos, behaviours.clone());
+                        let loot = Loot {
+                            drops: loot.clone(),
+                        };
+                        Monster::push(cb, world_pos, brain, loot, None, self.pos);
+                    }
+                    SpawnTemplate::School { count } => {
+                        let school = rng.rand();
+                        let heading = Vec2::from_angle(rng.gen_range(0.0, std::f32::consts::TAU));
+                        for _ in 0..*count {
+                            let offset =
+                                Vec2::new(rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0));
+                            Fish::push(
+                                cb,
+                                world_pos + offset * 0.15,
+                                heading * 0.5,
+                                school,
+                                self.pos,
+                            );
+                        }
+                    }
+                    SpawnTemplate::Air { radius, rate } => {
+                        AirSource::push(cb, world_pos, *radius, *rate, self.pos);
                     }
                 }
+                spawned += 1;
             }
         }
     }
+
+    /// Pushes the objects of the rooms placed over this chunk (each object belongs to the
+    /// chunk it lies in, even when its room spills over several chunks).
+    fn spawn_room_objects(&self, cm: &ChunkManager, cb: &mut CommandBuffer) {
+        let size = cm.chunk_size_in_tiles.as_ivec2();
+        let min = self.pos * size;
+        for (room, origin) in cm.rooms.placements_in(min, min + size) {
+            for (tile_pos, object) in &room.objects {
+                let tile_pos = origin.as_vec2() + *tile_pos;
+                if tile_pos.floor().as_ivec2().div_euclid(size) != self.pos {
+                    continue;
+                }
+                let world_pos = tile_pos * cm.tile_size_in_meters;
+
+                match object {
+                    RoomObject::Item {
+                        item,
+                        image_path,
+                        quantity,
+                    } => match ItemDef::by_name(item) {
+                        Some(def) => {
+                            WorldItem::push(
+                                cb,
+                                world_pos,
+                                def,
+                                *quantity,
+                                image_path.clone(),
+                                self.pos,
+                            );
+                        }
+                        None => eprintln!("Item desconhecido na sala {}: {}", room.name, item),
+                    },
+                    RoomObject::Monster => {
+                        let brain = Brain::new(world_pos, Vec::new());
+                        Monster::push(cb, world_pos, brain, Loot::default(), None, self.pos);
+                    }
+                    RoomObject::SpawnPoint { kind } => {
+                        self.push_spawn_point(world_pos, kind.clone(), cb)
+                    }
+                    RoomObject::Air { radius, rate } => {
+                        AirSource::push(cb, world_pos, *radius, *rate, self.pos);
+                    }
+                }
+            }
+        }
+    }
+
+    fn push_spawn_point(&self, position: Vec2, kind: String, cb: &mut CommandBuffer) {
+        cb.push((
+            Transform {
+                position,
+                ..Default::default()
+            },
+            SpawnPoint {
+                kind,
+                chunk: self.pos,
+            },
+        ));
+    }
 }
 
 /// a Chunk can be in 3 states:
@@ -230,14 +572,17 @@ pub struct ChunkBody {
     pub pos: IVec2,
     pub state: ChunkState,
     pub body_handle: Option<RigidBodyHandle>,
-    pub collider_handles: Matrix<Option<ColliderHandle>>,
+    pub colliders: ChunkColliders,
 }
 
+/// The colliders of a chunk body, with the cell rows each one covers.
+pub type ChunkColliders = Vec<(ColliderHandle, Range<usize>)>;
+
 impl ChunkBody {
-    pub fn new(size: UVec2, pos: IVec2) -> Self {
+    pub fn new(pos: IVec2) -> Self {
         Self {
             body_handle: None,
-            collider_handles: Matrix::new(size.x as usize, size.y as usize, None),
+            colliders: Vec::new(),
             pos,
             state: ChunkState::Freed,
         }
@@ -255,12 +600,12 @@ impl ChunkBody {
             return;
         }
 
-        if let Ok((body_handle, collider_handles)) = self.gen_matrix(chunk, cm, pc) {
+        if let Ok((body_handle, colliders)) = self.gen_matrix(chunk, cm, pc) {
             cb.add_component(
                 *e,
                 ChunkBody {
                     body_handle,
-                    collider_handles,
+                    colliders,
                     state: ChunkState::Loaded,
                     ..self.clone()
                 },
@@ -268,13 +613,7 @@ impl ChunkBody {
         }
     }
 
-    pub fn unload(
-        &self,
-        e: &Entity,
-        cm: &ChunkManager,
-        pc: &mut PhysicsContext,
-        cb: &mut CommandBuffer,
-    ) {
+    pub fn unload(&self, e: &Entity, pc: &mut PhysicsContext, cb: &mut CommandBuffer) {
         if self.state != ChunkState::Loaded {
             return;
         }
@@ -285,11 +624,7 @@ impl ChunkBody {
             *e,
             ChunkBody {
                 body_handle: None,
-                collider_handles: Matrix::new(
-                    cm.chunk_size_in_tiles.x as usize,
-                    cm.chunk_size_in_tiles.y as usize,
-                    None,
-                ),
+                colliders: Vec::new(),
                 state: ChunkState::Unloaded,
                 ..self.clone()
             },
@@ -301,52 +636,104 @@ impl ChunkBody {
         chunk: &Chunk,
         cm: &ChunkManager,
         pc: &mut PhysicsContext,
-    ) -> Result<(Option<RigidBodyHandle>, Matrix<Option<ColliderHandle>>), String> {
-        let size_x = (cm.chunk_size_in_tiles.x + 1) as usize;
-        let size_y = (cm.chunk_size_in_tiles.y + 1) as usize;
-
-        let mut col_matrix = Matrix::new(size_x, size_y, None);
-
+    ) -> Result<(Option<RigidBodyHandle>, ChunkColliders), String> {
         let og_matrix = match &chunk.matrix {
             Some(m) => m,
             _ => return Err("Chunk sem matrix ainda".into()),
         };
 
-        let mut visited_matrix = Matrix::<bool>::new(og_matrix.width, og_matrix.height, false);
-
         let rb = self.create_new_body(cm);
         let rb = self.insert_new_chunk_body(rb, pc);
-        for y in 0..og_matrix.height {
-            for x in 0..og_matrix.width {
-                let tile = og_matrix[(x, y)];
-                if tile == 0 {
-                    continue;
-                }
-                if visited_matrix[(x, y)] == true {
-                    continue;
-                }
-                let tile_pos = UVec2::new(x as u32, y as u32);
-
-                // Colisão por linha de tiles
-                let mut w = 1;
-                while x + w < og_matrix.width
-                    && og_matrix[(x + w, y)] != 0
-                    && !visited_matrix[(x + w, y)]
-                {
-                    w += 1;
-                }
+        let colliders = self.build_colliders(og_matrix, 0..og_matrix.height - 1, rb, cm, pc);
 
-                for dx in 0..w {
-                    visited_matrix[(x + dx, y)] = true;
-                }
+        Ok((Some(rb), colliders))
+    }
 
-                let col = self.create_new_tile_collider(cm, tile_pos, w);
-                let col_handle = self.insert_tile(rb, col, pc);
-                col_matrix[(x, y)] = Some(col_handle);
+    /// Builds the colliders of the cell rows `rows`, depending on `cm.collider_mode`.
+    /// Contours always take the whole chunk.
+    fn build_colliders(
+        &self,
+        og_matrix: &Matrix<u32>,
+        rows: Range<usize>,
+        rb: RigidBodyHandle,
+        cm: &ChunkManager,
+        pc: &mut PhysicsContext,
+    ) -> ChunkColliders {
+        let cols: Vec<(Collider, Range<usize>)> = match cm.collider_mode {
+            ColliderMode::Greedy => {
+                let rects = shapes::greedy_rects(og_matrix, rows.clone())
+                    .into_iter()
+                    .map(|rect| {
+                        (
+                            self.create_new_tile_collider(cm, rect),
+                            rect.y..rect.y + rect.h,
+                        )
+                    });
+                let slopes = rows
+                    .flat_map(|y| (0..og_matrix.width - 1).map(move |x| (x, y)))
+                    .filter_map(|(x, y)| {
+                        let cell_pos = UVec2::new(x as u32, y as u32);
+                        let slope = self.create_new_slope_collider(
+                            cm,
+                            cell_pos,
+                            marching_index(og_matrix, x, y),
+                        )?;
+                        Some((slope, y..y + 1))
+                    });
+                rects.chain(slopes).collect()
             }
+            ColliderMode::Contour => shapes::contours(og_matrix)
+                .into_iter()
+                .filter_map(|line| self.create_new_contour_collider(cm, &line))
+                .map(|col| (col, 0..og_matrix.height - 1))
+                .collect(),
+        };
+
+        cols.into_iter()
+            .map(|(col, rows)| (self.insert_tile(rb, col, pc), rows))
+            .collect()
+    }
+
+    /// Rebuilds the colliders after the tiles of the corner rows `edited` changed. Only
+    /// the colliders around them are redone: the cells those corners touch plus a row of
+    /// margin, grown until no merged rect sticks out. Contours redo the whole chunk.
+    pub fn rebuild(
+        &mut self,
+        chunk: &Chunk,
+        edited: RangeInclusive<usize>,
+        cm: &ChunkManager,
+        pc: &mut PhysicsContext,
+    ) {
+        let (Some(rb), Some(og_matrix)) = (self.body_handle, &chunk.matrix) else {
+            return;
+        };
+
+        let cell_rows = og_matrix.height - 1;
+        let mut rows = edited.start().saturating_sub(2)..(edited.end() + 2).min(cell_rows);
+        loop {
+            let grown = self
+                .colliders
+                .iter()
+                .filter(|(_, r)| r.start < rows.end && rows.start < r.end)
+                .fold(rows.clone(), |band, (_, r)| {
+                    band.start.min(r.start)..band.end.max(r.end)
+                });
+            if grown == rows {
+                break;
+            }
+            rows = grown;
         }
 
-        Ok((Some(rb), col_matrix))
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.colliders)
+            .into_iter()
+            .partition(|(_, r)| r.start < rows.end && rows.start < r.end);
+        for (handle, _) in removed {
+            pc.colliders
+                .remove(handle, &mut pc.islands, &mut pc.bodies, true);
+        }
+        let rebuilt = self.build_colliders(og_matrix, rows, rb, cm, pc);
+        self.colliders = kept;
+        self.colliders.extend(rebuilt);
     }
 
     fn clear_matrix(&self, pc: &mut PhysicsContext) {
@@ -386,25 +773,55 @@ impl ChunkBody {
             .build()
     }
 
-    fn create_new_tile_collider(
+    fn create_new_tile_collider(&self, cm: &ChunkManager, rect: CellRect) -> Collider {
+        let size = vec2(rect.w as f32, rect.h as f32) * cm.tile_size_in_meters;
+        let center_local = vec2(rect.x as f32, rect.y as f32) * cm.tile_size_in_meters + size / 2.;
+
+        ColliderBuilder::cuboid(size.x / 2., size.y / 2.)
+            .collision_groups(CollisionLayer::Wall.interaction_groups())
+            .translation(vector![center_local.x, center_local.y])
+            .build()
+    }
+
+    fn create_new_contour_collider(&self, cm: &ChunkManager, line: &[Vec2]) -> Option<Collider> {
+        if line.len() < 2 {
+            return None;
+        }
+        let points: Vec<_> = line
+            .iter()
+            .map(|p| {
+                let p = *p * cm.tile_size_in_meters;
+                point![p.x, p.y]
+            })
+            .collect();
+
+        Some(
+            ColliderBuilder::polyline(points, None)
+                .collision_groups(CollisionLayer::Wall.interaction_groups())
+                .build(),
+        )
+    }
+
+    fn create_new_slope_collider(
         &self,
         cm: &ChunkManager,
-        initial_pos: UVec2,
-        width: usize,
-    ) -> Collider {
-        let center_local = vector![
-            (initial_pos.x as f32 + width as f32 / 2.) * cm.tile_size_in_meters.x,
-            (initial_pos.y as f32 + 0.5) * cm.tile_size_in_meters.y
-        ];
-
-        let half_extents = vector![
-            (width as f32 * cm.tile_size_in_meters.x) / 2.,
-            cm.tile_size_in_meters.y / 2.,
-        ];
-
-        ColliderBuilder::cuboid(half_extents.x, half_extents.y)
-            .user_data(surface_type_to_bit(SurfaceType::Wall))
-            .translation(center_local)
-            .build()
+        cell_pos: UVec2,
+        index: u32,
+    ) -> Option<Collider> {
+        let points: Vec<_> = autotile::slope_polygon(index)?
+            .iter()
+            .map(|&(x, y)| {
+                point![
+                    (cell_pos.x as f32 + x) * cm.tile_size_in_meters.x,
+                    (cell_pos.y as f32 + y) * cm.tile_size_in_meters.y
+                ]
+            })
+            .collect();
+
+        Some(
+            ColliderBuilder::convex_hull(&points)?
+                .collision_groups(CollisionLayer::Wall.interaction_groups())
+                .build(),
+        )
     }
 }
@@ -0,0 +1,50 @@
+use legion::{Entity, systems::CommandBuffer};
+use macroquad::{
+    color::Color,
+    math::{IVec2, Vec2},
+};
+
+use super::{DebugSprite, Transform};
+
+/// A small fish of a school. Fish have no rapier body: they move their own `Transform`
+/// (see `sys::fish`), so there can be hundreds of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fish {
+    /// Fish flock with the ones of the same school, and keep apart from the others.
+    pub school: u32,
+    /// In m/s.
+    pub velocity: Vec2,
+    pub chunk: IVec2,
+    pub active: bool,
+}
+
+impl Fish {
+    pub fn push(
+        cb: &mut CommandBuffer,
+        position: Vec2,
+        velocity: Vec2,
+        school: u32,
+        chunk: IVec2,
+    ) -> Entity {
+        // Each school gets its own shade
+        let shade = (school % 7) as f32 / 7.0;
+        cb.push((
+            Transform {
+                position,
+                rotation: velocity.to_angle(),
+                ..Default::default()
+            },
+            DebugSprite {
+                size: Vec2::new(0.06, 0.03),
+                color: Color::new(0.9, 0.6 + shade * 0.3, 0.2 + shade * 0.5, 1.0),
+                z_order: 0.9,
+            },
+            Fish {
+                school,
+                velocity,
+                chunk,
+                active: true,
+            },
+        ))
+    }
+}
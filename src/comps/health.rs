@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+/// Who is on whose side: damage only hurts the other factions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Faction {
+    Player,
+    Monster,
+}
+
+/// Seconds a target can't be hurt again after a hit, so a lasting contact hurts in
+/// steps instead of every frame.
+pub const HIT_COOLDOWN: f32 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    pub faction: Faction,
+    /// Seconds left of `HIT_COOLDOWN`.
+    pub invulnerable: f32,
+}
+
+impl Health {
+    pub fn new(max: f32, faction: Faction) -> Self {
+        Self {
+            current: max,
+            max,
+            faction,
+            invulnerable: 0.0,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    /// Takes `amount` regardless of invulnerability, for harm over time.
+    pub fn lose(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+    }
+
+    /// Takes `amount` if not invulnerable. Returns whether it hurt.
+    pub fn hurt(&mut self, amount: f32) -> bool {
+        if self.invulnerable > 0.0 || self.is_dead() {
+            return false;
+        }
+        self.current = (self.current - amount).max(0.0);
+        self.invulnerable = HIT_COOLDOWN;
+        true
+    }
+}
+
+/// Hurts what its body touches: projectiles and monster bites alike.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Damage {
+    pub amount: f32,
+    pub faction: Faction,
+    /// Projectiles are gone after the first hit; melee keeps biting.
+    pub consumed_on_hit: bool,
+}
+
+/// An item dropped on death, with `chance` in 0..1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LootDrop {
+    pub item: String,
+    pub image_path: String,
+    #[serde(default = "one")]
+    pub quantity: u8,
+    #[serde(default = "always")]
+    pub chance: f32,
+}
+
+fn one() -> u8 {
+    1
+}
+
+fn always() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Loot {
+    pub drops: Vec<LootDrop>,
+}
@@ -5,7 +5,10 @@ pub struct InventoryHolder {
     pub inventory: Inventory,
 }
 
-use macroquad::math::IVec2;
+use legion::{Entity, systems::CommandBuffer};
+use macroquad::math::{IVec2, Vec2};
+
+use super::{Body, CollisionLayer, Sprite, Transform};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct WorldItem {
@@ -14,3 +17,47 @@ pub struct WorldItem {
     pub chunk: IVec2,
     pub active: bool,
 }
+
+impl WorldItem {
+    /// Pushes an item lying in the world, with its body and sprite.
+    pub fn push(
+        cb: &mut CommandBuffer,
+        position: Vec2,
+        item: ItemDef,
+        quantity: u8,
+        image_path: String,
+        chunk: IVec2,
+    ) -> Entity {
+        cb.push(Self::bundle(position, item, quantity, image_path, chunk))
+    }
+
+    /// The components of `push` as a tuple. Scenes are loaded straight into the `World`
+    /// before the schedules run, so `scene::load_scene` pushes these instead.
+    pub fn bundle(
+        position: Vec2,
+        item: ItemDef,
+        quantity: u8,
+        image_path: String,
+        chunk: IVec2,
+    ) -> (Transform, Body, Sprite, WorldItem) {
+        (
+            Transform {
+                position,
+                ..Default::default()
+            },
+            Body::new(Vec2::new(0.1, 0.1), true).with_layer(CollisionLayer::Item),
+            Sprite {
+                image_path,
+                z_order: 1.0,
+                flip_x: false,
+                flip_y: false,
+            },
+            WorldItem {
+                item,
+                quantity,
+                chunk,
+                active: true,
+            },
+        )
+    }
+}
@@ -0,0 +1,40 @@
+use macroquad::color::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightShape {
+    /// Lights all around.
+    Point,
+    /// Lights `angle` radians wide, centered on the entity's `Transform::rotation`.
+    Cone { angle: f32 },
+}
+
+/// Light around an entity, stopped by the chunk walls (see `sys::light`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightSource {
+    pub shape: LightShape,
+    /// How far it reaches, in meters, fading to nothing.
+    pub radius: f32,
+    pub color: Color,
+    /// Brightness at the center, in 0..1.
+    pub intensity: f32,
+}
+
+impl LightSource {
+    pub fn point(radius: f32, color: Color, intensity: f32) -> Self {
+        Self {
+            shape: LightShape::Point,
+            radius,
+            color,
+            intensity,
+        }
+    }
+
+    pub fn cone(angle: f32, radius: f32, color: Color, intensity: f32) -> Self {
+        Self {
+            shape: LightShape::Cone { angle },
+            radius,
+            color,
+            intensity,
+        }
+    }
+}
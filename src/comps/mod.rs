@@ -1,17 +1,27 @@
+pub mod ai;
 pub mod chunk;
+pub mod fish;
+pub mod health;
 pub mod physics;
 pub mod render;
 pub mod tags;
 pub mod weapon;
 pub mod world;
 pub mod hud;
+pub mod oxygen;
 pub mod inventory;
+pub mod light;
 
+pub use ai::*;
 pub use chunk::*;
+pub use fish::*;
+pub use health::*;
 pub use physics::*;
 pub use render::*;
 pub use tags::*;
 pub use weapon::*;
 pub use world::*;
 pub use hud::*;
+pub use oxygen::*;
 pub use inventory::*;
+pub use light::*;
@@ -0,0 +1,102 @@
+use legion::{Entity, systems::CommandBuffer};
+use macroquad::{
+    color::Color,
+    math::{IVec2, Vec2},
+};
+
+use super::{Body, CollisionLayer, DebugSprite, LightSource, Transform};
+
+/// The air in the diver's tank, in liters. It drains every second, faster deep down and
+/// while swimming hard; once empty the diver suffocates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oxygen {
+    pub current: f32,
+    pub capacity: f32,
+    /// Liters per second at rest, at the surface.
+    pub drain_rate: f32,
+    /// Extra drain per chunk of depth, as a fraction of `drain_rate`.
+    pub depth_drain: f32,
+    /// Extra drain when swimming at full speed, as a fraction of `drain_rate`.
+    pub exertion_drain: f32,
+    /// Health lost per second with the tank empty.
+    pub suffocation_damage: f32,
+}
+
+impl Oxygen {
+    pub fn new(capacity: f32, drain_rate: f32) -> Self {
+        Self {
+            current: capacity,
+            capacity,
+            drain_rate,
+            depth_drain: 0.25,
+            exertion_drain: 1.0,
+            suffocation_damage: 10.0,
+        }
+    }
+
+    /// Liters per second at `depth` (chunks) and `exertion` (0 resting, 1 full speed).
+    pub fn drain_per_second(&self, depth: f32, exertion: f32) -> f32 {
+        self.drain_rate
+            * (1.0
+                + depth.max(0.0) * self.depth_drain
+                + exertion.clamp(0.0, 1.0) * self.exertion_drain)
+    }
+
+    pub fn refill(&mut self, liters: f32) {
+        self.current = (self.current + liters).min(self.capacity);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+/// Somewhere to breathe: an air pocket or a bubble vent, refilling `rate` liters per
+/// second to a diver inside its sensor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AirSource {
+    pub rate: f32,
+    /// Half the side of the sensor, in meters.
+    pub radius: f32,
+    pub chunk: IVec2,
+}
+
+impl AirSource {
+    pub fn push(
+        cb: &mut CommandBuffer,
+        position: Vec2,
+        radius: f32,
+        rate: f32,
+        chunk: IVec2,
+    ) -> Entity {
+        cb.push(Self::bundle(position, radius, rate, chunk))
+    }
+
+    /// See `WorldItem::bundle`.
+    pub fn bundle(
+        position: Vec2,
+        radius: f32,
+        rate: f32,
+        chunk: IVec2,
+    ) -> (Transform, Body, DebugSprite, LightSource, AirSource) {
+        (
+            Transform {
+                position,
+                ..Default::default()
+            },
+            Body::new(Vec2::splat(radius), false).with_layer(CollisionLayer::Sensor),
+            DebugSprite {
+                size: Vec2::splat(radius * 2.0),
+                color: Color::new(0.7, 0.9, 1.0, 0.35),
+                z_order: 0.5,
+            },
+            // The bubbles glow a little, to be found in the dark
+            LightSource::point(radius * 3.0, Color::new(0.6, 0.85, 1.0, 1.0), 0.5),
+            AirSource {
+                rate,
+                radius,
+                chunk,
+            },
+        )
+    }
+}
@@ -1,10 +1,12 @@
 use macroquad::math::Vec2;
 use nalgebra::vector;
 use rapier2d::prelude::{
-    ColliderBuilder, ColliderHandle, ColliderSet, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+    ActiveEvents, Collider, ColliderBuilder, ColliderHandle, ColliderSet, Group, InteractionGroups,
+    RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
 };
 
 use super::Transform;
+use crate::resources::physics_events::CONTACT_FORCE_THRESHOLD;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BodyType {
@@ -12,24 +14,116 @@ pub enum BodyType {
     Rect,
 }
 
+/// What a collider is, deciding what it collides with (see `collides_with`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollisionLayer {
+    Player,
+    Wall,
+    Monster,
+    Item,
+    Projectile,
+    /// Detects overlaps without pushing anything.
+    Sensor,
+}
+
+impl CollisionLayer {
+    pub const ALL: [CollisionLayer; 6] = [
+        CollisionLayer::Player,
+        CollisionLayer::Wall,
+        CollisionLayer::Monster,
+        CollisionLayer::Item,
+        CollisionLayer::Projectile,
+        CollisionLayer::Sensor,
+    ];
+
+    /// Symmetric: a pair collides only if both agree.
+    pub fn collides_with(self, other: CollisionLayer) -> bool {
+        use CollisionLayer::*;
+        let (a, b) = if self as u8 <= other as u8 {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        matches!(
+            (a, b),
+            (Player, Wall | Monster | Sensor)
+                | (Wall, Monster | Item | Projectile)
+                | (Monster, Monster | Projectile | Sensor)
+                | (Item, Sensor)
+        )
+    }
+
+    pub fn group(self) -> Group {
+        Group::from_bits_truncate(1 << self as u32)
+    }
+
+    pub fn interaction_groups(self) -> InteractionGroups {
+        let filter = Self::ALL
+            .into_iter()
+            .filter(|other| self.collides_with(*other))
+            .fold(Group::NONE, |groups, other| groups | other.group());
+        InteractionGroups::new(self.group(), filter)
+    }
+
+    /// The layer of a collider, from its collision groups.
+    pub fn of(collider: &Collider) -> Option<CollisionLayer> {
+        let memberships = collider.collision_groups().memberships;
+        Self::ALL
+            .into_iter()
+            .find(|layer| memberships == layer.group())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Body {
     pub body_handle: Option<RigidBodyHandle>,
     pub collider_handle: Option<ColliderHandle>,
     pub is_dynamic: bool,
     pub size: Vec2,
+    pub layer: CollisionLayer,
+    /// How much of its weight the water holds up: under 1 it sinks, over 1 it floats.
+    pub buoyancy: f32,
+    /// Damping of the linear and angular velocities, the water's resistance. Currents
+    /// push harder on bodies with more linear drag.
+    pub linear_drag: f32,
+    pub angular_drag: f32,
 }
 
 impl Body {
+    /// A body slowly sinking through the water. Fixed bodies go on the `Wall` layer and
+    /// dynamic ones on the `Item` layer; anything else needs `with_layer`.
     pub fn new(size: Vec2, is_dynamic: bool) -> Self {
         Body {
             body_handle: None,
             collider_handle: None,
             size,
             is_dynamic,
+            layer: match is_dynamic {
+                true => CollisionLayer::Item,
+                false => CollisionLayer::Wall,
+            },
+            buoyancy: 0.9,
+            linear_drag: 1.5,
+            angular_drag: 1.5,
         }
     }
 
+    pub fn with_layer(mut self, layer: CollisionLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_buoyancy(mut self, buoyancy: f32) -> Self {
+        self.buoyancy = buoyancy;
+        self
+    }
+
+    pub fn with_drag(mut self, linear: f32, angular: f32) -> Self {
+        self.linear_drag = linear;
+        self.angular_drag = angular;
+        self
+    }
+
     pub fn load(
         &mut self,
         body: BodyType,
@@ -39,6 +133,9 @@ impl Body {
     ) {
         let mut rb = RigidBodyBuilder::dynamic()
             .translation(vector![transform.position.x, transform.position.y])
+            .gravity_scale(1.0 - self.buoyancy)
+            .linear_damping(self.linear_drag)
+            .angular_damping(self.angular_drag)
             .build();
         if !self.is_dynamic {
             rb = RigidBodyBuilder::fixed()
@@ -46,13 +143,20 @@ impl Body {
                 .build();
         }
         let col = match body {
-            BodyType::Circle => ColliderBuilder::ball(self.size.x).build(),
-            BodyType::Rect => ColliderBuilder::cuboid(self.size.x, self.size.y).build(),
+            BodyType::Circle => ColliderBuilder::ball(self.size.x),
+            BodyType::Rect => ColliderBuilder::cuboid(self.size.x, self.size.y),
             _ => {
                 eprintln!("Erro: função load não suporta esse tipo de corpo");
                 return;
             }
         };
+        // Reported to `PhysicsEvents`; chunk colliders don't need it, the other side has it
+        let col = col
+            .collision_groups(self.layer.interaction_groups())
+            .sensor(self.layer == CollisionLayer::Sensor)
+            .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
+            .contact_force_event_threshold(CONTACT_FORCE_THRESHOLD)
+            .build();
         self.body_handle = Some(rigid_bodies.insert(rb));
         self.collider_handle =
             Some(colliders.insert_with_parent(col, self.body_handle.unwrap(), rigid_bodies));
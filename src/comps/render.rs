@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use macroquad::{
     color::Color,
     math::{IVec2, IVec4, Vec2},
+    texture::RenderTarget,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -49,3 +50,12 @@ pub struct TileMap {
 pub struct TileMapSource {
     pub matrix: Vec<Vec<u32>>,
 }
+
+/// The tiles of a chunk drawn once into a texture (at the tileset resolution), so the
+/// chunk is a single quad. Redrawn by `bake_chunks_system` only when `Chunk::revision`
+/// changes, and kept while the chunk is unloaded, so it's still seen without its matrix.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkBake {
+    pub target: Option<RenderTarget>,
+    pub revision: u32,
+}
@@ -6,6 +6,13 @@ pub struct Monster {
     pub chunk: IVec2,
 }
 
+/// A place where something of `kind` (e.g. "monster") may appear, set by room objects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpawnPoint {
+    pub kind: String,
+    pub chunk: IVec2,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Player {
     pub speed: f32,
@@ -9,7 +9,22 @@ use super::Body;
 
 #[derive(Debug, Clone)]
 pub struct WeaponHolder {
+    /// The weapon in hand, a copy of `loadout[slot]` while it's out.
     pub weapon: Option<Box<dyn Weapon>>,
+    /// Every weapon carried, in hotbar order.
+    pub loadout: Vec<Box<dyn Weapon>>,
+    pub slot: usize,
+}
+
+impl WeaponHolder {
+    /// Carries `loadout`, with the first weapon in hand.
+    pub fn new(loadout: Vec<Box<dyn Weapon>>) -> Self {
+        Self {
+            weapon: loadout.first().cloned(),
+            loadout,
+            slot: 0,
+        }
+    }
 }
 
 pub trait Weapon: Debug + Send + Sync + Renderable + Any {
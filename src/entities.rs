@@ -2,7 +2,10 @@ use std::{collections::HashMap};
 
 use crate::{
     comps::*,
-    resources::{inventory::Inventory, weapons::DebugGun},
+    resources::{
+        inventory::Inventory,
+        weapons::{DebugGun, Harpoon},
+    },
 };
 use egui_macroquad::egui;
 use legion::World;
@@ -16,7 +19,7 @@ pub fn populate(world: &mut World) {
     let mut points: HashMap<String, Mat3> = HashMap::new();
     let weapon = Mat3::from_translation(Vec2::new(0.35, 0.));
     points.insert("weapon".to_string(), weapon);
-    world.push((
+    let player = world.push((
         Transform {
             anchor_points: points,
             ..Default::default()
@@ -28,14 +31,18 @@ pub fn populate(world: &mut World) {
             flip_y: false,
         },
         Player { speed: 100. },
-        Body::new(Vec2::new(0.32 / 2., 0.32 / 2.), true),
-        WeaponHolder {
-            //weapon: Some(Box::new(Harpoon::default())),
-            weapon: Some(Box::new(DebugGun {
+        Health::new(100., Faction::Player),
+        // High drag, so the diver speeds up and stops quickly
+        Body::new(Vec2::new(0.32 / 2., 0.32 / 2.), true).with_layer(CollisionLayer::Player)
+            .with_buoyancy(1.0)
+            .with_drag(6.0, 1.5),
+        WeaponHolder::new(vec![
+            Box::new(DebugGun {
                 active: false,
                 cooldown: 0.1,
-            })),
-        },
+            }),
+            Box::new(Harpoon::default()),
+        ]),
         InventoryHolder { inventory: Inventory::default() },
         //inventory window
         Window {
@@ -43,6 +50,12 @@ pub fn populate(world: &mut World) {
             build_func: None
         }
     ));
+    // Past the components a tuple can push at once
+    if let Some(mut entry) = world.entry(player) {
+        entry.add_component(Oxygen::new(100., 1.));
+        // The flashlight, pointed by `Transform::rotation`, which follows `look_direction`
+        entry.add_component(LightSource::cone(0.9, 6.0, color::Color::new(1.0, 0.95, 0.8, 1.0), 0.9));
+    }
 
     //block
     world.push((
@@ -55,7 +68,7 @@ pub fn populate(world: &mut World) {
             color: color::WHITE,
             z_order: -1.,
         },
-        Body::new(Vec2::new(1.0 / 2., 1.0 / 2.), false),
+        Body::new(Vec2::new(1.0 / 2., 1.0 / 2.), false).with_layer(CollisionLayer::Wall),
     ));
 
     //tilemap
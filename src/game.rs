@@ -1,29 +1,44 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use fastnoise_lite::{FastNoiseLite, NoiseType};
-use legion::{Resources, World};
+use legion::{IntoQuery, Resources, World};
 use macroquad::{
     camera::Camera2D,
     input::{KeyCode, is_key_down, is_key_pressed},
-    math::{UVec2, Vec2},
-    miniquad::date::now,
+    math::{IVec2, UVec2, Vec2},
     time::get_frame_time,
     window::next_frame,
 };
 
 use crate::{
+    comps::Chunk,
     entities::populate,
     load::{load, physics_load},
     resources::{
+        biome::{BiomeMap, default_biomes},
+        chunk_jobs::ChunkJobs,
         chunk_manager::ChunkManager,
+        chunk_store::ChunkStore,
+        combat::{Deaths, GameState},
+        config::GameConfig,
+        currents::Currents,
+        explored::ExploredMap,
+        flock::{FlockGrid, FlockParams},
         input::{InputContext, InputSetup},
-        physics, *,
+        lighting::Lighting,
+        navigation::PathCache,
+        physics,
+        rooms::{Rooms, default_room_rules},
+        spawn_table::SpawnTables,
+        world_gen::{Pipeline, RoomStamp},
+        *,
     },
+    scene::{load_scene, scene_size_in_tiles},
     sys,
 };
 
 pub async fn run_game() -> Result<(), String> {
     let mut loaded_textures = HashMap::new();
+    let config = GameConfig::from_args();
 
     loop {
         let mut world = World::default();
@@ -35,23 +50,70 @@ pub async fn run_game() -> Result<(), String> {
         resources.insert(Textures(loaded_textures));
         resources.insert(InputContext::new(InputSetup::default()));
         resources.insert(crate::resources::GuiCommandBuffer::default());
+        resources.insert(crate::resources::tile_edits::TileEdits::default());
+        resources.insert(Deaths::default());
+        resources.insert(GameState::default());
+
+        let seed = config.world_seed();
+        println!("Seed do mundo: {}", seed);
+
+        let chunk_size_in_tiles = UVec2::ONE * 16;
+        let tile_size_in_meters = Vec2::ONE * 0.16;
+        let biomes = Arc::new(BiomeMap::new(
+            seed,
+            chunk_size_in_tiles.as_vec2() * tile_size_in_meters,
+            default_biomes(),
+        ));
 
-        let mut noise = FastNoiseLite::new();
-        noise.set_seed(Some(now().floor() as i32));
-        noise.set_noise_type(Some(NoiseType::Perlin));
-
-        resources.insert(ChunkManager::new(
-            noise,
-            Vec2::ONE * 40.0,
-            0.01,
-            UVec2::ONE * 16,
-            Vec2::ONE * 0.16,
+        let mut rooms = Rooms::new(seed, chunk_size_in_tiles, default_room_rules());
+        let scene_origin = Vec2::ZERO;
+        if let Some(scene) = &config.scene {
+            // The cave is carved out where the scene goes, its border corners included
+            match scene_size_in_tiles(scene) {
+                Ok(size) => {
+                    let min = (scene_origin / tile_size_in_meters).round().as_ivec2();
+                    rooms.reserve(min, min + size + IVec2::ONE);
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+        let rooms = Arc::new(rooms);
+        let generator = Pipeline::default()
+            .then_boxed(config.world_generator(seed, &biomes))
+            .then(RoomStamp {
+                rooms: rooms.clone(),
+            });
+
+        let mut cm = ChunkManager::new(
+            seed,
+            biomes.clone(),
+            Box::new(generator),
+            chunk_size_in_tiles,
+            tile_size_in_meters,
             9,
             12,
-        ));
+        );
+        cm.collider_mode = config.collider_mode;
+        cm.rooms = rooms;
+        cm.spawns = Arc::new(SpawnTables::load("assets/spawns.ron")?);
+        resources.insert(ChunkJobs::new(&cm, ChunkJobs::default_workers(), 2));
+        resources.insert(cm);
+        resources.insert(ChunkStore::new(config.chunk_store_dir(seed)));
+        resources.insert(PathCache::default());
+        resources.insert(FlockGrid::default());
+        resources.insert(FlockParams::default());
+        resources.insert(Currents::new(seed, biomes.clone()));
+        resources.insert(Lighting::default());
+        resources.insert(ExploredMap::new(chunk_size_in_tiles));
         resources.insert(Box::new(Camera2D::default()));
 
         populate(&mut world);
+        if let Some(scene) = &config.scene {
+            let cm = resources.get::<ChunkManager>().unwrap();
+            if let Err(err) = load_scene(&mut world, scene, scene_origin, &cm) {
+                eprintln!("{}", err);
+            }
+        }
         let (mut step_schedule, mut draw_schedule) = sys::populate();
 
         // Systems involving macroquad rendering or input requires local thread
@@ -66,7 +128,8 @@ pub async fn run_game() -> Result<(), String> {
             };
             resources.insert(dt);
 
-            if is_key_pressed(KeyCode::R) {
+            let game_over = resources.get::<GameState>().unwrap().game_over;
+            if is_key_pressed(KeyCode::R) || (game_over && is_key_pressed(KeyCode::Enter)) {
                 should_restart = true;
                 next_frame().await;
                 break 'running;
@@ -82,6 +145,14 @@ pub async fn run_game() -> Result<(), String> {
             next_frame().await
         }
 
+        // The chunks still around weren't recorded nor written yet
+        {
+            let mut store = resources.get_mut::<ChunkStore>().unwrap();
+            for chunk in <&Chunk>::query().iter(&world) {
+                store.record_entities(chunk.pos, chunk.to_records(&world));
+            }
+            store.flush_all();
+        }
         loaded_textures = resources.remove::<Textures>().unwrap().0;
 
         if !should_restart {
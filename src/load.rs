@@ -8,7 +8,7 @@ use rapier2d::prelude::{ColliderBuilder, RigidBodyBuilder};
 
 use crate::{
     comps::{Body, Sprite, Spritesheet, TileMap, Transform, WeaponHolder},
-    resources::{Textures, physics::PhysicsContext},
+    resources::{Textures, chunk_manager::ChunkManager, physics::PhysicsContext},
 };
 
 pub async fn load(world: &mut World, resources: &mut Resources) {
@@ -35,11 +35,29 @@ pub async fn load(world: &mut World, resources: &mut Resources) {
         }
     }
 
+    // Chunks are created later, so their tilesets come from the biomes
+    if let Some(cm) = resources.get::<ChunkManager>() {
+        for biome in &cm.biomes.biomes {
+            if !textures.contains_key(&biome.tileset_path)
+                && !img_paths.contains(&biome.tileset_path)
+            {
+                img_paths.push(biome.tileset_path.clone());
+            }
+        }
+        // Same for the items they spawn
+        for path in cm.spawns.image_paths() {
+            if !textures.contains_key(path) && !img_paths.contains(path) {
+                img_paths.push(path.clone());
+            }
+        }
+    }
+
     let mut query = <&WeaponHolder>::query();
     for holder in query.iter(world) {
-        if let Some(weapon) = &holder.weapon {
-            if !textures.contains_key(&weapon.image_path()) {
-                img_paths.push(weapon.image_path());
+        for weapon in holder.weapon.iter().chain(holder.loadout.iter()) {
+            let path = weapon.image_path();
+            if !textures.contains_key(&path) && !img_paths.contains(&path) {
+                img_paths.push(path);
             }
         }
     }
@@ -6,6 +6,7 @@ mod entities;
 mod game;
 mod load;
 mod resources;
+mod scene;
 mod sys;
 
 fn conf() -> Conf {
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use fastnoise_lite::{FastNoiseLite, NoiseType};
+use macroquad::math::{IVec2, Vec2, ivec2};
+
+use crate::common::{autotile::AutotileLayout, rng::noise_seed};
+
+#[derive(Debug, Clone)]
+pub struct Biome {
+    pub name: String,
+    /// Depth, in chunks, where the biome starts. It goes until the next biome's top.
+    pub top: f32,
+    pub noise_scale: Vec2,
+    /// Solid where the cave noise is over it: higher means more open water.
+    pub threshold: f32,
+    /// Top speed of the water currents, in m/s (see `Currents`).
+    pub current_strength: f32,
+    pub tileset_path: String,
+    pub tile_size_in_tileset: Vec2,
+    pub tiles: HashMap<u32, IVec2>,
+}
+
+/// Biomes stacked by depth. A second noise moves the borders up and down, so they aren't
+/// flat lines, and near a border the two biomes are blended.
+pub struct BiomeMap {
+    /// Sorted by `top`.
+    pub biomes: Vec<Biome>,
+    noise: FastNoiseLite,
+    pub chunk_size_in_meters: Vec2,
+    /// How far, in chunks, the biome noise moves a border.
+    pub border_wobble: f32,
+    /// Height, in chunks, of the band where two biomes are mixed.
+    pub blend: f32,
+}
+
+impl BiomeMap {
+    pub fn new(seed: u64, chunk_size_in_meters: Vec2, mut biomes: Vec<Biome>) -> Self {
+        biomes.sort_by(|a, b| a.top.total_cmp(&b.top));
+
+        let mut noise = FastNoiseLite::new();
+        // Not the cave noise seed, or borders would follow the caves
+        noise.set_seed(Some(noise_seed(seed.rotate_left(17))));
+        noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+        noise.set_frequency(Some(0.08));
+
+        Self {
+            biomes,
+            noise,
+            chunk_size_in_meters,
+            border_wobble: 1.5,
+            blend: 1.0,
+        }
+    }
+
+    /// Depth in chunks of a world position, moved by the biome noise.
+    pub fn depth(&self, world_pos: Vec2) -> f32 {
+        let pos = world_pos / self.chunk_size_in_meters;
+        pos.y + self.noise.get_noise_2d(pos.x, pos.y) * self.border_wobble
+    }
+
+    /// The biomes at a world position with their weight (summing to 1). Two of them
+    /// near a border, one elsewhere.
+    pub fn weights(&self, world_pos: Vec2) -> Vec<(&Biome, f32)> {
+        let depth = self.depth(world_pos);
+        let i = self.index_at(depth);
+        let biome = &self.biomes[i];
+        let half = self.blend / 2.0;
+
+        if let Some(next) = self.biomes.get(i + 1)
+            && next.top - depth < half
+        {
+            let t = smoothstep((depth - (next.top - half)) / self.blend);
+            return vec![(biome, 1.0 - t), (next, t)];
+        }
+        if i > 0 && depth - biome.top < half {
+            let t = smoothstep((depth - (biome.top - half)) / self.blend);
+            return vec![(&self.biomes[i - 1], 1.0 - t), (biome, t)];
+        }
+        vec![(biome, 1.0)]
+    }
+
+    /// The biome that weighs the most at a world position.
+    pub fn dominant(&self, world_pos: Vec2) -> &Biome {
+        &self.biomes[self.index_at(self.depth(world_pos))]
+    }
+
+    //=====PRIVATE======
+    fn index_at(&self, depth: f32) -> usize {
+        self.biomes
+            .iter()
+            .rposition(|b| b.top <= depth)
+            .unwrap_or(0)
+    }
+}
+
+fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Shallow reef, mid-water cave and abyss, each with its own sheet (the reef and abyss
+/// ones are recolours of `assets/dungeon_tiles.png`).
+pub fn default_biomes() -> Vec<Biome> {
+    vec![
+        Biome {
+            name: "Recife".into(),
+            top: f32::NEG_INFINITY,
+            noise_scale: Vec2::ONE * 30.0,
+            threshold: 0.2,
+            current_strength: 0.4,
+            tileset_path: "assets/reef_tiles.png".into(),
+            tile_size_in_tileset: Vec2::new(8., 8.),
+            // Rocks in water (the island block), inner corners from the pool block
+            tiles: AutotileLayout {
+                block_min: ivec2(37, 5),
+                block_max: ivec2(40, 8),
+                inner_top_left: ivec2(34, 8),
+                inner_top_right: ivec2(30, 8),
+                inner_bottom_right: ivec2(30, 5),
+                inner_bottom_left: ivec2(34, 5),
+            }
+            .tiles(),
+        },
+        Biome {
+            name: "Caverna".into(),
+            top: 3.0,
+            noise_scale: Vec2::ONE * 40.0,
+            threshold: 0.01,
+            current_strength: 0.15,
+            tileset_path: "assets/dungeon_tiles.png".into(),
+            tile_size_in_tileset: Vec2::new(8., 8.),
+            // Big wall block, inner corners from the pit block
+            tiles: AutotileLayout {
+                block_min: ivec2(4, 4),
+                block_max: ivec2(13, 13),
+                inner_top_left: ivec2(19, 8),
+                inner_top_right: ivec2(15, 8),
+                inner_bottom_right: ivec2(15, 5),
+                inner_bottom_left: ivec2(19, 5),
+            }
+            .tiles(),
+        },
+        Biome {
+            name: "Abismo".into(),
+            top: 10.0,
+            noise_scale: Vec2::ONE * 55.0,
+            threshold: -0.08,
+            current_strength: 0.05,
+            tileset_path: "assets/abyss_tiles.png".into(),
+            tile_size_in_tileset: Vec2::new(8., 8.),
+            tiles: AutotileLayout {
+                block_min: ivec2(4, 32),
+                block_max: ivec2(9, 36),
+                inner_top_left: ivec2(19, 8),
+                inner_top_right: ivec2(15, 8),
+                inner_bottom_right: ivec2(15, 5),
+                inner_bottom_left: ivec2(19, 5),
+            }
+            .tiles(),
+        },
+    ]
+}
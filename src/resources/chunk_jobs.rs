@@ -0,0 +1,150 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc, Condvar, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+};
+
+use macroquad::math::IVec2;
+
+use super::chunk_manager::{ChunkGen, ChunkManager};
+use crate::common::Matrix;
+
+struct JobList {
+    chunks: Vec<IVec2>,
+    player_chunk: IVec2,
+    closed: bool,
+}
+
+struct JobQueue {
+    list: Mutex<JobList>,
+    wake: Condvar,
+}
+
+/// Generates chunk matrices in worker threads, so crossing a chunk border doesn't stall
+/// the frame. Workers always take the queued chunk nearest to the player, and the
+/// finished matrices are handed back (nearest first) at most `budget` per frame.
+pub struct ChunkJobs {
+    queue: Arc<JobQueue>,
+    results: Mutex<Receiver<(IVec2, Matrix<u32>)>>,
+    /// Received, waiting for a frame with budget left.
+    ready: Vec<(IVec2, Matrix<u32>)>,
+    /// Queued, being generated or ready, so a chunk isn't asked twice.
+    pending: HashSet<IVec2>,
+    /// How many chunks get their matrix (and, separately, their colliders) per frame.
+    pub budget: usize,
+}
+
+impl ChunkJobs {
+    pub fn new(cm: &ChunkManager, workers: usize, budget: usize) -> Self {
+        let queue = Arc::new(JobQueue {
+            list: Mutex::new(JobList {
+                chunks: Vec::new(),
+                player_chunk: cm.player_chunk,
+                closed: false,
+            }),
+            wake: Condvar::new(),
+        });
+        let (sender, receiver) = mpsc::channel();
+
+        for i in 0..workers.max(1) {
+            let queue = queue.clone();
+            let sender = sender.clone();
+            let chunk_gen = cm.chunk_gen();
+            let spawned = thread::Builder::new()
+                .name(format!("chunk-gen-{}", i))
+                .spawn(move || Self::work(&queue, &sender, &chunk_gen));
+            if let Err(err) = spawned {
+                eprintln!("Erro criando thread de geração de chunks: {}", err);
+            }
+        }
+
+        Self {
+            queue,
+            results: Mutex::new(receiver),
+            ready: Vec::new(),
+            pending: HashSet::new(),
+            budget,
+        }
+    }
+
+    /// One worker per spare core, leaving one for the game loop.
+    pub fn default_workers() -> usize {
+        thread::available_parallelism()
+            .map(|n| n.get().saturating_sub(1))
+            .unwrap_or(1)
+            .clamp(1, 4)
+    }
+
+    pub fn request(&mut self, chunk: IVec2) {
+        if !self.pending.insert(chunk) {
+            return;
+        }
+        self.queue.list.lock().unwrap().chunks.push(chunk);
+        self.queue.wake.notify_one();
+    }
+
+    /// Moves the priority center and drops the queued chunks that are now out of range.
+    pub fn update(&mut self, player_chunk: IVec2, unloading_distance: i32) {
+        let mut list = self.queue.list.lock().unwrap();
+        list.player_chunk = player_chunk;
+        let pending = &mut self.pending;
+        list.chunks.retain(|chunk| {
+            let keep = chunk.distance_squared(player_chunk) < unloading_distance;
+            if !keep {
+                pending.remove(chunk);
+            }
+            keep
+        });
+    }
+
+    /// The finished matrices for this frame, nearest to `player_chunk` first.
+    pub fn take_ready(&mut self, player_chunk: IVec2) -> Vec<(IVec2, Matrix<u32>)> {
+        self.ready.extend(self.results.lock().unwrap().try_iter());
+        self.ready
+            .sort_by_key(|(chunk, _)| std::cmp::Reverse(chunk.distance_squared(player_chunk)));
+
+        let count = self.budget.min(self.ready.len());
+        let taken: Vec<_> = (0..count).filter_map(|_| self.ready.pop()).collect();
+        for (chunk, _) in &taken {
+            self.pending.remove(chunk);
+        }
+        taken
+    }
+
+    //=====PRIVATE======
+    fn work(queue: &JobQueue, sender: &Sender<(IVec2, Matrix<u32>)>, chunk_gen: &ChunkGen) {
+        loop {
+            let chunk = {
+                let mut list = queue.list.lock().unwrap();
+                while list.chunks.is_empty() && !list.closed {
+                    list = queue.wake.wait(list).unwrap();
+                }
+                if list.closed {
+                    return;
+                }
+                let player_chunk = list.player_chunk;
+                let (nearest, _) = list
+                    .chunks
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, chunk)| chunk.distance_squared(player_chunk))
+                    .unwrap();
+                list.chunks.swap_remove(nearest)
+            };
+
+            if sender.send((chunk, chunk_gen.generate(chunk))).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for ChunkJobs {
+    fn drop(&mut self) {
+        self.queue.list.lock().unwrap().closed = true;
+        self.queue.wake.notify_all();
+    }
+}
@@ -1,28 +1,57 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
-use super::uvec2_to_vec2;
-use fastnoise_lite::FastNoiseLite;
+use super::{
+    biome::BiomeMap,
+    rooms::Rooms,
+    spawn_table::SpawnTables,
+    uvec2_to_vec2,
+    world_gen::{GenRegion, WorldGenerator},
+};
+use crate::common::{
+    Matrix,
+    rng::{RngStream, chunk_rng},
+};
 use legion::Entity;
-use macroquad::math::{IVec2, UVec2, Vec2};
+use macroquad::{
+    math::{IVec2, UVec2, Vec2},
+    rand::RandGenerator,
+};
+
+/// How the solid cells of a chunk become colliders.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColliderMode {
+    /// Full cells merged in rectangles, plus one polygon per slope.
+    Greedy,
+    /// Only the outline of the rock, as polylines. Fewest colliders and nothing to snag
+    /// on, but they are hollow: a body that ends up inside the rock isn't pushed out.
+    Contour,
+}
 
 pub struct ChunkManager {
     pub chunks: HashMap<IVec2, Entity>,
-    pub world_noise: FastNoiseLite,
-    pub noise_scale: Vec2,
-    pub threshold: f32,
+    /// Drives every random decision of the world: noise, item rolls, monster spawns.
+    pub seed: u64,
+    pub biomes: Arc<BiomeMap>,
+    /// Also stamped by the generator (see `RoomStamp`); here for the room objects.
+    pub rooms: Arc<Rooms>,
+    /// What populates a chunk the first time it loads.
+    pub spawns: Arc<SpawnTables>,
+    /// Shared with the generation workers, see `ChunkJobs`.
+    pub generator: Arc<dyn WorldGenerator>,
     pub player_chunk: IVec2,
     pub chunk_size_in_tiles: UVec2,
     pub tile_size_in_meters: Vec2,
     pub chunk_size_in_meters: Vec2,
+    pub collider_mode: ColliderMode,
     pub unloading_distance: i32,
     pub freeing_distance: i32,
 }
 
 impl ChunkManager {
     pub fn new(
-        world_noise: FastNoiseLite,
-        noise_scale: Vec2,
-        threshold: f32,
+        seed: u64,
+        biomes: Arc<BiomeMap>,
+        generator: Box<dyn WorldGenerator>,
         chunk_size_in_tiles: UVec2,
         tile_size_in_meters: Vec2,
         unloading_distance: i32,
@@ -30,15 +59,95 @@ impl ChunkManager {
     ) -> Self {
         Self {
             chunks: HashMap::new(),
-            world_noise,
-            noise_scale,
-            threshold,
+            seed,
+            biomes,
+            rooms: Arc::new(Rooms::new(seed, chunk_size_in_tiles, Vec::new())),
+            spawns: Arc::new(SpawnTables::default()),
+            generator: Arc::from(generator),
             player_chunk: IVec2::ZERO,
             chunk_size_in_tiles,
             tile_size_in_meters,
             chunk_size_in_meters: uvec2_to_vec2(chunk_size_in_tiles) * tile_size_in_meters,
+            collider_mode: ColliderMode::Greedy,
             unloading_distance,
             freeing_distance,
         }
     }
+
+    pub fn chunk_rng(&self, chunk: IVec2, stream: RngStream) -> RandGenerator {
+        chunk_rng(self.seed, chunk, stream)
+    }
+
+    /// What a worker thread needs to generate chunks without touching the manager.
+    pub fn chunk_gen(&self) -> ChunkGen {
+        ChunkGen {
+            seed: self.seed,
+            generator: self.generator.clone(),
+            chunk_size_in_tiles: self.chunk_size_in_tiles,
+            tile_size_in_meters: self.tile_size_in_meters,
+        }
+    }
+
+    /// World tile under a position in meters.
+    /// Tiles are the corners of the grid, at `tile * tile_size`: the nearest one to `pos`.
+    pub fn world_to_tile(&self, pos: Vec2) -> IVec2 {
+        (pos / self.tile_size_in_meters).round().as_ivec2()
+    }
+
+    pub fn tile_to_world(&self, tile: IVec2) -> Vec2 {
+        tile.as_vec2() * self.tile_size_in_meters
+    }
+
+    /// Chunk matrices overlap by one row and one column (a chunk has `size + 1` tiles per
+    /// side), so a world tile can live in up to 4 chunks. Returns each `(chunk, local tile)`.
+    pub fn chunks_of_tile(&self, tile: IVec2) -> Vec<(IVec2, UVec2)> {
+        let size = self.chunk_size_in_tiles.as_ivec2();
+        let chunk = tile.div_euclid(size);
+        let local = tile.rem_euclid(size);
+
+        let xs = if local.x == 0 { vec![0, 1] } else { vec![0] };
+        let ys = if local.y == 0 { vec![0, 1] } else { vec![0] };
+
+        let mut out = Vec::new();
+        for &dy in &ys {
+            for &dx in &xs {
+                let offset = IVec2::new(dx, dy);
+                out.push((chunk - offset, (local + offset * size).as_uvec2()));
+            }
+        }
+        out
+    }
+}
+
+#[derive(Clone)]
+pub struct ChunkGen {
+    pub seed: u64,
+    pub generator: Arc<dyn WorldGenerator>,
+    pub chunk_size_in_tiles: UVec2,
+    pub tile_size_in_meters: Vec2,
+}
+
+impl ChunkGen {
+    /// The `(size + 1) x (size + 1)` corner matrix of a chunk, before any edit.
+    pub fn generate(&self, chunk: IVec2) -> Matrix<u32> {
+        let pad = self.generator.padding();
+        let size = self.chunk_size_in_tiles.as_ivec2() + 1;
+        let origin = chunk * self.chunk_size_in_tiles.as_ivec2() - pad as i32;
+
+        let mut padded = Matrix::new(size.x as usize + pad * 2, size.y as usize + pad * 2, 0);
+        let region = GenRegion {
+            origin,
+            tile_size_in_meters: self.tile_size_in_meters,
+            seed: self.seed,
+        };
+        self.generator.generate(&region, &mut padded);
+
+        let mut matrix = Matrix::new(size.x as usize, size.y as usize, 0);
+        for y in 0..matrix.height {
+            for x in 0..matrix.width {
+                matrix[(x, y)] = padded[(x + pad, y + pad)];
+            }
+        }
+        matrix
+    }
 }
@@ -0,0 +1,183 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use macroquad::math::IVec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::Matrix,
+    comps::{Behaviour, LootDrop},
+    resources::inventory::ItemDef,
+};
+
+/// What changed in a chunk since it was generated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkDelta {
+    /// Edited tiles as `(x, y) -> tile`, applied over the generated matrix.
+    pub tiles: HashMap<(u32, u32), u32>,
+    /// `None` while the chunk was never freed. After that, this list replaces the
+    /// random spawn, so picked items and killed monsters don't come back.
+    pub entities: Option<Vec<EntityRecord>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EntityRecord {
+    Item {
+        position: [f32; 2],
+        item: ItemDef,
+        quantity: u8,
+        image_path: String,
+    },
+    Monster {
+        position: [f32; 2],
+        #[serde(default)]
+        home: Option<[f32; 2]>,
+        #[serde(default)]
+        behaviours: Vec<Behaviour>,
+        #[serde(default)]
+        loot: Vec<LootDrop>,
+        /// Full if not given.
+        #[serde(default)]
+        health: Option<f32>,
+    },
+    SpawnPoint {
+        position: [f32; 2],
+        kind: String,
+    },
+    Air {
+        position: [f32; 2],
+        radius: f32,
+        rate: f32,
+    },
+    Fish {
+        position: [f32; 2],
+        velocity: [f32; 2],
+        school: u32,
+    },
+}
+
+/// Keeps the chunk deltas in memory, and optionally in `dir` as one file per chunk.
+/// Files are read the first time a chunk is needed, and written when it is freed, when
+/// the game ends or restarts, or when `flush` finds tile edits not saved yet.
+pub struct ChunkStore {
+    deltas: HashMap<IVec2, ChunkDelta>,
+    /// Chunks known to have no file, so they aren't looked up again.
+    absent: HashSet<IVec2>,
+    /// Chunks with tile edits not written yet.
+    dirty: HashSet<IVec2>,
+    dir: Option<PathBuf>,
+}
+
+impl ChunkStore {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        if let Some(dir) = &dir
+            && let Err(err) = fs::create_dir_all(dir)
+        {
+            eprintln!("Erro criando {}: {}", dir.display(), err);
+        }
+
+        Self {
+            deltas: HashMap::new(),
+            absent: HashSet::new(),
+            dirty: HashSet::new(),
+            dir,
+        }
+    }
+
+    pub fn get(&mut self, pos: IVec2) -> Option<&ChunkDelta> {
+        if !self.deltas.contains_key(&pos) {
+            let delta = self.read(pos)?;
+            self.deltas.insert(pos, delta);
+        }
+        self.deltas.get(&pos)
+    }
+
+    pub fn record_tile(&mut self, pos: IVec2, x: u32, y: u32, tile: u32) {
+        self.get_or_default(pos).tiles.insert((x, y), tile);
+        self.dirty.insert(pos);
+    }
+
+    pub fn record_entities(&mut self, pos: IVec2, entities: Vec<EntityRecord>) {
+        self.get_or_default(pos).entities = Some(entities);
+        self.write(pos);
+    }
+
+    /// Writes the chunk if it has unsaved tile edits.
+    pub fn flush(&mut self, pos: IVec2) {
+        if self.dirty.contains(&pos) {
+            self.write(pos);
+        }
+    }
+
+    /// Writes every chunk with unsaved tile edits, e.g. before leaving the game.
+    pub fn flush_all(&mut self) {
+        let dirty: Vec<IVec2> = self.dirty.iter().copied().collect();
+        for pos in dirty {
+            self.write(pos);
+        }
+    }
+
+    pub fn apply_tiles(&mut self, pos: IVec2, matrix: &mut Matrix<u32>) {
+        if let Some(delta) = self.get(pos) {
+            for (&(x, y), &tile) in delta.tiles.iter() {
+                matrix.set(x as usize, y as usize, tile);
+            }
+        }
+    }
+
+    //=====PRIVATE======
+    fn get_or_default(&mut self, pos: IVec2) -> &mut ChunkDelta {
+        if !self.deltas.contains_key(&pos) {
+            let delta = self.read(pos).unwrap_or_default();
+            self.absent.remove(&pos);
+            self.deltas.insert(pos, delta);
+        }
+        self.deltas.get_mut(&pos).unwrap()
+    }
+
+    fn file_path(dir: &Path, pos: IVec2) -> PathBuf {
+        dir.join(format!("chunk_{}_{}.ron", pos.x, pos.y))
+    }
+
+    fn read(&mut self, pos: IVec2) -> Option<ChunkDelta> {
+        if self.absent.contains(&pos) {
+            return None;
+        }
+        let path = Self::file_path(self.dir.as_ref()?, pos);
+        let Ok(text) = fs::read_to_string(&path) else {
+            self.absent.insert(pos);
+            return None;
+        };
+        match ron::from_str::<ChunkDelta>(&text) {
+            Ok(mut delta) => {
+                for record in delta.entities.iter_mut().flatten() {
+                    if let EntityRecord::Item { item, .. } = record {
+                        *item = item.clone().restore();
+                    }
+                }
+                Some(delta)
+            }
+            Err(err) => {
+                eprintln!("Erro lendo {}: {}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    fn write(&mut self, pos: IVec2) {
+        self.dirty.remove(&pos);
+        let (Some(dir), Some(delta)) = (&self.dir, self.deltas.get(&pos)) else {
+            return;
+        };
+        let path = Self::file_path(dir, pos);
+        let result = ron::to_string(delta)
+            .map_err(|e| e.to_string())
+            .and_then(|text| fs::write(&path, text).map_err(|e| e.to_string()));
+        if let Err(err) = result {
+            eprintln!("Erro salvando {}: {}", path.display(), err);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use legion::Entity;
+use macroquad::math::Vec2;
+
+use crate::comps::Faction;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub faction: Faction,
+}
+
+/// The deaths of this frame, for whoever cares (score, sounds...). Cleared when the
+/// next frame's deaths are handled.
+#[derive(Debug, Default)]
+pub struct Deaths {
+    pub events: Vec<DeathEvent>,
+}
+
+/// Set when the player dies; the game waits for a restart then.
+#[derive(Debug, Default)]
+pub struct GameState {
+    pub game_over: bool,
+}
@@ -0,0 +1,88 @@
+use std::{path::PathBuf, sync::Arc};
+
+use macroquad::miniquad::date::now;
+
+use super::{
+    biome::BiomeMap,
+    chunk_manager::ColliderMode,
+    world_gen::{self, WorldGenerator},
+};
+
+/// Options read from the command line, e.g. `cargo run -- --seed 1234`.
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    pub seed: Option<u64>,
+    /// Where modified chunks are saved, one folder per seed. In memory only if `None`.
+    pub chunk_dir: Option<PathBuf>,
+    /// Cave style, see `world_gen::by_name`.
+    pub caves: String,
+    pub collider_mode: ColliderMode,
+    /// A Tiled map loaded as it is at the world origin, see `scene::load_scene`.
+    pub scene: Option<PathBuf>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            chunk_dir: None,
+            caves: "biomes".into(),
+            collider_mode: ColliderMode::Greedy,
+            scene: None,
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn from_args() -> Self {
+        let mut config = GameConfig::default();
+        let mut args = std::env::args().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => match args.next().map(|s| s.parse::<u64>()) {
+                    Some(Ok(seed)) => config.seed = Some(seed),
+                    _ => eprintln!("Erro: --seed espera um número inteiro positivo"),
+                },
+                "--chunk-dir" => match args.next() {
+                    Some(dir) => config.chunk_dir = Some(PathBuf::from(dir)),
+                    None => eprintln!("Erro: --chunk-dir espera um caminho"),
+                },
+                "--caves" => match args.next() {
+                    Some(caves) => config.caves = caves,
+                    None => eprintln!("Erro: --caves espera biomes, noise, cellular ou smooth"),
+                },
+                "--colliders" => match args.next().as_deref() {
+                    Some("greedy") => config.collider_mode = ColliderMode::Greedy,
+                    Some("contour") => config.collider_mode = ColliderMode::Contour,
+                    _ => eprintln!("Erro: --colliders espera greedy ou contour"),
+                },
+                "--scene" => match args.next() {
+                    Some(path) => config.scene = Some(PathBuf::from(path)),
+                    None => eprintln!("Erro: --scene espera um caminho para um .tmx"),
+                },
+                other => eprintln!("Argumento desconhecido: {}", other),
+            }
+        }
+
+        config
+    }
+
+    /// The seed given by the user, or a new one based on the clock.
+    pub fn world_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| (now() * 1000.0) as u64)
+    }
+
+    pub fn world_generator(&self, seed: u64, biomes: &Arc<BiomeMap>) -> Box<dyn WorldGenerator> {
+        world_gen::by_name(&self.caves, seed, biomes).unwrap_or_else(|| {
+            eprintln!("Estilo de caverna desconhecido: {}", self.caves);
+            world_gen::by_name("biomes", seed, biomes).unwrap()
+        })
+    }
+
+    pub fn chunk_store_dir(&self, seed: u64) -> Option<PathBuf> {
+        self.chunk_dir
+            .as_ref()
+            .map(|dir| dir.join(seed.to_string()))
+    }
+}
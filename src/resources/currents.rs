@@ -0,0 +1,55 @@
+use std::{f32::consts::TAU, sync::Arc};
+
+use fastnoise_lite::{FastNoiseLite, NoiseType};
+use macroquad::math::Vec2;
+
+use super::biome::BiomeMap;
+use crate::common::rng::noise_seed;
+
+/// The water flow, a noise vector field that slowly changes with time. Its strength
+/// comes from the biomes (`Biome::current_strength`), blended near their borders.
+pub struct Currents {
+    noise: FastNoiseLite,
+    biomes: Arc<BiomeMap>,
+    /// How fast the field changes, in noise units per second.
+    pub drift: f32,
+    time: f32,
+}
+
+impl Currents {
+    pub fn new(seed: u64, biomes: Arc<BiomeMap>) -> Self {
+        let mut noise = FastNoiseLite::new();
+        // Not the cave or biome seeds, or the flow would follow the rock
+        noise.set_seed(Some(noise_seed(seed.rotate_left(29))));
+        noise.set_noise_type(Some(NoiseType::OpenSimplex2));
+        noise.set_frequency(Some(0.15));
+        Self {
+            noise,
+            biomes,
+            drift: 0.5,
+            time: 0.0,
+        }
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.time += dt * self.drift;
+    }
+
+    /// The water velocity at a world position, in m/s.
+    pub fn sample(&self, pos: Vec2) -> Vec2 {
+        let strength: f32 = self
+            .biomes
+            .weights(pos)
+            .iter()
+            .map(|(biome, weight)| biome.current_strength * weight)
+            .sum();
+        if strength == 0.0 {
+            return Vec2::ZERO;
+        }
+
+        let angle = self.noise.get_noise_3d(pos.x, pos.y, self.time) * TAU;
+        // Far from the angle sample, so speed and direction don't go together
+        let speed = self.noise.get_noise_3d(pos.x + 1000.0, pos.y, self.time) * 0.5 + 0.5;
+        Vec2::from_angle(angle) * speed * strength
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use macroquad::math::{IVec2, UVec2, Vec2};
+
+/// A bit per tile of a chunk, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitmap {
+    bits: Vec<u64>,
+    width: usize,
+}
+
+impl Bitmap {
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            bits: vec![0; (size.x * size.y).div_ceil(64) as usize],
+            width: size.x as usize,
+        }
+    }
+
+    pub fn get(&self, tile: UVec2) -> bool {
+        let i = tile.y as usize * self.width + tile.x as usize;
+        self.bits[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    pub fn set(&mut self, tile: UVec2, value: bool) {
+        let i = tile.y as usize * self.width + tile.x as usize;
+        if value {
+            self.bits[i / 64] |= 1 << (i % 64);
+        } else {
+            self.bits[i / 64] &= !(1 << (i % 64));
+        }
+    }
+}
+
+/// What the player has seen of a chunk: which tiles, and of those which were rock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExploredChunk {
+    pub seen: Bitmap,
+    pub rock: Bitmap,
+    /// Goes up on every change, so the map knows when to redraw the chunk.
+    pub revision: u32,
+}
+
+impl ExploredChunk {
+    pub fn new(size: UVec2) -> Self {
+        Self {
+            seen: Bitmap::new(size),
+            rock: Bitmap::new(size),
+            revision: 0,
+        }
+    }
+}
+
+/// The fog of war: the explored tiles of every chunk, by chunk. Kept here rather than
+/// in `Chunk`, so a chunk is still on the map after it unloads and even after it's
+/// freed. Also holds the markers the player placed.
+pub struct ExploredMap {
+    pub chunks: HashMap<IVec2, ExploredChunk>,
+    pub chunk_size_in_tiles: UVec2,
+    /// World positions, in meters.
+    pub markers: Vec<Vec2>,
+    /// How far, in meters, the diver sees around them.
+    pub sight: f32,
+}
+
+impl ExploredMap {
+    pub fn new(chunk_size_in_tiles: UVec2) -> Self {
+        Self {
+            chunks: HashMap::new(),
+            chunk_size_in_tiles,
+            markers: Vec::new(),
+            sight: 4.0,
+        }
+    }
+
+    /// Marks a world tile as seen. Returns false if it already was, the same way.
+    pub fn reveal(&mut self, tile: IVec2, rock: bool) -> bool {
+        let size = self.chunk_size_in_tiles;
+        let local = tile.rem_euclid(size.as_ivec2()).as_uvec2();
+        let chunk = self
+            .chunks
+            .entry(tile.div_euclid(size.as_ivec2()))
+            .or_insert_with(|| ExploredChunk::new(size));
+        if chunk.seen.get(local) && chunk.rock.get(local) == rock {
+            return false;
+        }
+        chunk.seen.set(local, true);
+        chunk.rock.set(local, rock);
+        chunk.revision += 1;
+        true
+    }
+
+    /// Places a marker at `position`, or removes the one within `radius` of it.
+    pub fn toggle_marker(&mut self, position: Vec2, radius: f32) {
+        match self
+            .markers
+            .iter()
+            .position(|marker| marker.distance(position) <= radius)
+        {
+            Some(i) => {
+                self.markers.swap_remove(i);
+            }
+            None => self.markers.push(position),
+        }
+    }
+}
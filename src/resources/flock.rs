@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use macroquad::math::{IVec2, Vec2};
+
+/// Flocking weights and distances, in meters and m/s.
+#[derive(Debug, Clone)]
+pub struct FlockParams {
+    /// How far a fish sees the others of its school.
+    pub view_radius: f32,
+    /// Closer than this, fish push each other away.
+    pub separation_radius: f32,
+    pub separation: f32,
+    pub alignment: f32,
+    pub cohesion: f32,
+    /// How far a fish sees the player and the monsters.
+    pub threat_radius: f32,
+    pub flee: f32,
+    /// How far ahead a fish looks for rock.
+    pub look_ahead: f32,
+    pub avoidance: f32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Max change of velocity, in m/s².
+    pub max_acceleration: f32,
+}
+
+impl Default for FlockParams {
+    fn default() -> Self {
+        Self {
+            view_radius: 0.4,
+            separation_radius: 0.1,
+            separation: 6.0,
+            alignment: 1.5,
+            cohesion: 1.0,
+            threat_radius: 1.2,
+            flee: 8.0,
+            look_ahead: 0.3,
+            avoidance: 10.0,
+            min_speed: 0.3,
+            max_speed: 1.2,
+            max_acceleration: 6.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FishSnapshot {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub school: u32,
+}
+
+/// Where every active fish and threat was at the start of the frame, bucketed by cells
+/// of `view_radius`, so each fish only looks at its neighbours. Rebuilt every frame
+/// before the fish move, which lets them all move in parallel.
+#[derive(Debug, Default)]
+pub struct FlockGrid {
+    cells: HashMap<IVec2, Vec<FishSnapshot>>,
+    cell_size: f32,
+    pub threats: Vec<Vec2>,
+}
+
+impl FlockGrid {
+    pub fn clear(&mut self, cell_size: f32) {
+        self.cells.clear();
+        self.threats.clear();
+        self.cell_size = cell_size;
+    }
+
+    pub fn insert(&mut self, fish: FishSnapshot) {
+        let cell = self.cell_of(fish.position);
+        self.cells.entry(cell).or_default().push(fish);
+    }
+
+    /// The fish in the cells around `pos`; the caller checks the actual distance.
+    pub fn near(&self, pos: Vec2) -> impl Iterator<Item = &FishSnapshot> {
+        let cell = self.cell_of(pos);
+        (-1..=1)
+            .flat_map(move |dy| (-1..=1).map(move |dx| cell + IVec2::new(dx, dy)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+
+    fn cell_of(&self, pos: Vec2) -> IVec2 {
+        (pos / self.cell_size).floor().as_ivec2()
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet};
 use macroquad::input::{
     KeyCode, MouseButton, is_key_down, is_key_pressed, is_key_released, is_mouse_button_down, is_mouse_button_released
 };
-use macroquad::input::{mouse_delta_position, mouse_position};
+use macroquad::input::{mouse_delta_position, mouse_position, mouse_wheel};
 use macroquad::math::Vec2;
 use macroquad::window::{screen_height, screen_width};
 
@@ -44,6 +44,8 @@ impl InputContext {
                 RawAction::MouseButton(b)   if !self.lock_mouse => is_mouse_button_down(*b),
                 RawAction::KeyUp(k)         if !self.lock_keybd => is_key_released(*k),
                 RawAction::MouseButtonUp(b) if !self.lock_mouse => is_mouse_button_released(*b),
+                RawAction::WheelUp          if !self.lock_mouse => mouse_wheel().1 > 0.,
+                RawAction::WheelDown        if !self.lock_mouse => mouse_wheel().1 < 0.,
                 _ => false
             };
 
@@ -64,6 +66,17 @@ pub enum InputAction {
     DebugActionOff,
     InventoryToggle,
     Interact,
+    CopySeed,
+    #[cfg(debug_assertions)]
+    DebugDig,
+    #[cfg(debug_assertions)]
+    DebugBuild,
+    MapToggle,
+    MapMarker,
+    /// Hotbar slot, from 0.
+    WeaponSlot(usize),
+    WeaponNext,
+    WeaponPrevious,
 }
 
 #[derive(PartialEq, Eq, Hash, Clone)]
@@ -73,6 +86,8 @@ pub enum RawAction {
     KeyOnce(KeyCode),
     MouseButton(MouseButton),
     MouseButtonUp(MouseButton),
+    WheelUp,
+    WheelDown,
 }
 pub struct InputSetup {
     pub keybindings: HashMap<RawAction, InputAction>,
@@ -97,6 +112,36 @@ impl Default for InputSetup {
             RawAction::KeyOnce(KeyCode::E), 
             InputAction::InventoryToggle
         );
+        keybindings.insert(RawAction::KeyOnce(KeyCode::F2), InputAction::CopySeed);
+        keybindings.insert(RawAction::KeyOnce(KeyCode::M), InputAction::MapToggle);
+        keybindings.insert(RawAction::KeyOnce(KeyCode::N), InputAction::MapMarker);
+        let slot_keys = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+        for (slot, key) in slot_keys.into_iter().enumerate() {
+            keybindings.insert(RawAction::KeyOnce(key), InputAction::WeaponSlot(slot));
+        }
+        keybindings.insert(RawAction::WheelDown, InputAction::WeaponNext);
+        keybindings.insert(RawAction::WheelUp, InputAction::WeaponPrevious);
+        #[cfg(debug_assertions)]
+        {
+            keybindings.insert(
+                RawAction::MouseButton(MouseButton::Right),
+                InputAction::DebugDig,
+            );
+            keybindings.insert(
+                RawAction::MouseButton(MouseButton::Middle),
+                InputAction::DebugBuild,
+            );
+        }
 
         InputSetup {
             keybindings,
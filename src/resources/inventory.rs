@@ -1,5 +1,6 @@
 use legion::{Entity, systems::CommandBuffer, world::SubWorld};
-use crate::comps::InventoryHolder;
+use serde::{Deserialize, Serialize};
+use crate::comps::{InventoryHolder, Oxygen};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Inventory {
@@ -63,9 +64,85 @@ pub struct ItemContext<'a> {
     pub world: &'a SubWorld<'a>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ItemDef {
     pub name: String,
     pub data: Vec<u8>,
+    /// Functions can't be saved, use `ItemDef::restore` to get it back by the item name.
+    #[serde(skip)]
     pub use_func : Option<fn(&mut CommandBuffer,&ItemContext)>
 }
+
+impl ItemDef {
+    /// Items known by name, so they can be recreated from saved data.
+    pub fn by_name(name: &str) -> Option<ItemDef> {
+        match name {
+            "Arma Debug" => Some(ItemDef {
+                name: name.into(),
+                data: vec![],
+                use_func: Some(|_cmd, _context| {
+                    println!("Usando arma debug!");
+                }),
+            }),
+            "Tanque de Ar" => Some(ItemDef {
+                name: name.into(),
+                data: vec![],
+                use_func: Some(|cmd, context| {
+                    use_up(cmd, context, "Tanque de Ar", |oxygen| {
+                        oxygen.current = oxygen.capacity
+                    });
+                }),
+            }),
+            "Tanque Maior" => Some(ItemDef {
+                name: name.into(),
+                data: vec![],
+                use_func: Some(|cmd, context| {
+                    use_up(cmd, context, "Tanque Maior", |oxygen| oxygen.capacity += 30.0);
+                }),
+            }),
+            "Regulador" => Some(ItemDef {
+                name: name.into(),
+                data: vec![],
+                use_func: Some(|cmd, context| {
+                    use_up(cmd, context, "Regulador", |oxygen| oxygen.drain_rate *= 0.8);
+                }),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Gives back the `use_func` lost when the item was saved.
+    pub fn restore(self) -> ItemDef {
+        match ItemDef::by_name(&self.name) {
+            Some(known) => ItemDef { data: self.data, ..known },
+            None => self,
+        }
+    }
+}
+
+/// Takes one `name` out of the holder's inventory and applies `effect` to its oxygen.
+fn use_up(cb: &mut CommandBuffer, ctx: &ItemContext, name: &'static str, effect: fn(&mut Oxygen)) {
+    let entity = ctx.holder_entity;
+    cb.exec_mut(move |world, _| {
+        let Some(mut entry) = world.entry(entity) else {
+            return;
+        };
+        let Ok(holder) = entry.get_component_mut::<InventoryHolder>() else {
+            return;
+        };
+        let item = holder
+            .inventory
+            .items
+            .iter()
+            .find_map(|slot| slot.item.clone().filter(|item| item.name == name));
+        let Some(item) = item else {
+            return;
+        };
+        if holder.inventory.remove_item(item, 1).is_err() {
+            return;
+        }
+        if let Ok(oxygen) = entry.get_component_mut::<Oxygen>() {
+            effect(oxygen);
+        }
+    });
+}
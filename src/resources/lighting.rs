@@ -0,0 +1,43 @@
+use macroquad::color::Color;
+
+/// How much daylight gets down to each depth. Everything a `LightSource` doesn't reach
+/// is lit by `ambient`.
+#[derive(Debug, Clone)]
+pub struct Lighting {
+    /// Ambient brightness at the surface, in 0..1.
+    pub surface: f32,
+    /// Ambient brightness at `dark_depth` and below.
+    pub deep: f32,
+    /// Depth, in chunks, where daylight is gone.
+    pub dark_depth: f32,
+    /// The color the water leaves to daylight.
+    pub tint: Color,
+    /// Rays cast per full turn of a light's visibility polygon.
+    pub rays: usize,
+}
+
+impl Default for Lighting {
+    fn default() -> Self {
+        Self {
+            surface: 1.0,
+            deep: 0.04,
+            dark_depth: 10.0,
+            tint: Color::new(0.7, 0.85, 1.0, 1.0),
+            rays: 96,
+        }
+    }
+}
+
+impl Lighting {
+    /// The light map color at `depth` (chunks) with no light source around.
+    pub fn ambient(&self, depth: f32) -> Color {
+        let t = (depth / self.dark_depth).clamp(0.0, 1.0);
+        let level = self.surface + (self.deep - self.surface) * t;
+        Color::new(
+            self.tint.r * level,
+            self.tint.g * level,
+            self.tint.b * level,
+            1.0,
+        )
+    }
+}
@@ -1,10 +1,25 @@
 //pub mod chunks;
+pub mod biome;
+pub mod chunk_jobs;
 pub mod chunk_manager;
+pub mod chunk_store;
+pub mod combat;
+pub mod config;
+pub mod currents;
+pub mod explored;
+pub mod flock;
 pub mod input;
+pub mod lighting;
+pub mod navigation;
 pub mod physics;
+pub mod physics_events;
 pub mod inventory;
 pub mod renderable;
+pub mod rooms;
+pub mod spawn_table;
+pub mod tile_edits;
 pub mod weapons;
+pub mod world_gen;
 pub mod gui_commands;
 pub use gui_commands::GuiCommandBuffer;
 use std::collections::HashMap;
@@ -0,0 +1,323 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use macroquad::math::{IVec2, UVec2, Vec2, ivec2};
+
+use super::chunk_manager::ChunkManager;
+use crate::{
+    common::Matrix,
+    comps::{Chunk, ChunkState},
+};
+
+/// The water tiles of the loaded chunks, by world tile. Tiles are matrix corners (see
+/// `calculate_tile_position`), and a tile of an unloaded chunk counts as rock.
+pub struct LoadedTiles<'a> {
+    matrices: HashMap<IVec2, &'a Matrix<u32>>,
+    chunk_size_in_tiles: IVec2,
+}
+
+impl<'a> LoadedTiles<'a> {
+    pub fn new(chunks: impl Iterator<Item = &'a Chunk>, chunk_size_in_tiles: UVec2) -> Self {
+        let matrices = chunks
+            .filter(|chunk| chunk.state == ChunkState::Loaded)
+            .filter_map(|chunk| Some((chunk.pos, chunk.matrix.as_ref()?)))
+            .collect();
+        Self {
+            matrices,
+            chunk_size_in_tiles: chunk_size_in_tiles.as_ivec2(),
+        }
+    }
+
+    pub fn is_water(&self, tile: IVec2) -> bool {
+        self.water(tile).unwrap_or(false)
+    }
+
+    /// Whether a tile is water, or `None` if its chunk isn't loaded.
+    pub fn water(&self, tile: IVec2) -> Option<bool> {
+        let size = self.chunk_size_in_tiles;
+        let matrix = self.matrices.get(&tile.div_euclid(size))?;
+        let local = tile.rem_euclid(size);
+        matrix
+            .get(local.x as usize, local.y as usize)
+            .map(|tile| *tile == 0)
+    }
+}
+
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+const NEIGHBOURS: [(IVec2, u32); 8] = [
+    (ivec2(1, 0), STRAIGHT_COST),
+    (ivec2(-1, 0), STRAIGHT_COST),
+    (ivec2(0, 1), STRAIGHT_COST),
+    (ivec2(0, -1), STRAIGHT_COST),
+    (ivec2(1, 1), DIAGONAL_COST),
+    (ivec2(-1, 1), DIAGONAL_COST),
+    (ivec2(1, -1), DIAGONAL_COST),
+    (ivec2(-1, -1), DIAGONAL_COST),
+];
+
+fn octile(a: IVec2, b: IVec2) -> u32 {
+    let d = (a - b).abs();
+    let (min, max) = (d.x.min(d.y) as u32, d.x.max(d.y) as u32);
+    DIAGONAL_COST * min + STRAIGHT_COST * (max - min)
+}
+
+/// A* over the water tiles, 8 ways, never cutting a rock corner. Gives up after
+/// expanding `max_nodes` tiles. Also returns the expanded tiles, which tell what the
+/// result depends on.
+pub fn find_path(
+    tiles: &LoadedTiles,
+    start: IVec2,
+    goal: IVec2,
+    max_nodes: usize,
+) -> (Option<Vec<IVec2>>, Vec<IVec2>) {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+    let mut cost: HashMap<IVec2, u32> = HashMap::from([(start, 0)]);
+    let mut expanded = Vec::new();
+    if !tiles.is_water(goal) {
+        return (None, expanded);
+    }
+    open.push(Reverse((octile(start, goal), start.x, start.y)));
+
+    while let Some(Reverse((_, x, y))) = open.pop() {
+        let current = ivec2(x, y);
+        if current == goal {
+            let mut path = vec![current];
+            let mut tile = current;
+            while let Some(&previous) = came_from.get(&tile) {
+                path.push(previous);
+                tile = previous;
+            }
+            path.reverse();
+            return (Some(path), expanded);
+        }
+        if expanded.len() >= max_nodes {
+            break;
+        }
+        expanded.push(current);
+
+        let current_cost = cost[&current];
+        for (step, step_cost) in NEIGHBOURS {
+            let next = current + step;
+            if !tiles.is_water(next) {
+                continue;
+            }
+            if step.x != 0
+                && step.y != 0
+                && !(tiles.is_water(current + ivec2(step.x, 0))
+                    && tiles.is_water(current + ivec2(0, step.y)))
+            {
+                continue;
+            }
+
+            let next_cost = current_cost + step_cost;
+            if cost.get(&next).is_none_or(|&c| next_cost < c) {
+                cost.insert(next, next_cost);
+                came_from.insert(next, current);
+                open.push(Reverse((next_cost + octile(next, goal), next.x, next.y)));
+            }
+        }
+    }
+    (None, expanded)
+}
+
+struct CachedPath {
+    path: Option<Vec<IVec2>>,
+    chunks: HashSet<IVec2>,
+}
+
+/// Remembers the paths found between tiles, failures included, until one of the chunks
+/// they looked at loads, unloads or is edited. Searches are limited per frame; past the
+/// budget, `next_waypoint` just points at the target.
+pub struct PathCache {
+    paths: HashMap<(IVec2, IVec2), CachedPath>,
+    by_chunk: HashMap<IVec2, HashSet<(IVec2, IVec2)>>,
+    pub max_nodes: usize,
+    pub searches_per_frame: usize,
+    /// Over it, the cache starts over.
+    pub capacity: usize,
+    searches_left: usize,
+}
+
+impl Default for PathCache {
+    fn default() -> Self {
+        Self {
+            paths: HashMap::new(),
+            by_chunk: HashMap::new(),
+            max_nodes: 2000,
+            searches_per_frame: 8,
+            capacity: 1024,
+            searches_left: 8,
+        }
+    }
+}
+
+impl PathCache {
+    pub fn begin_frame(&mut self) {
+        self.searches_left = self.searches_per_frame;
+    }
+
+    pub fn invalidate_chunk(&mut self, chunk: IVec2) {
+        for key in self.by_chunk.remove(&chunk).unwrap_or_default() {
+            if let Some(cached) = self.paths.remove(&key) {
+                for other in cached.chunks {
+                    if let Some(keys) = self.by_chunk.get_mut(&other) {
+                        keys.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The path between two tiles, from the cache or searched now. `None` if there is
+    /// none, or no search budget left this frame.
+    pub fn path(&mut self, tiles: &LoadedTiles, start: IVec2, goal: IVec2) -> Option<&[IVec2]> {
+        let key = (start, goal);
+        if !self.paths.contains_key(&key) {
+            if self.searches_left == 0 {
+                return None;
+            }
+            self.searches_left -= 1;
+            if self.paths.len() >= self.capacity {
+                self.paths.clear();
+                self.by_chunk.clear();
+            }
+
+            let (path, expanded) = find_path(tiles, start, goal, self.max_nodes);
+            // Neighbours are looked at too, so their chunks count
+            let size = tiles.chunk_size_in_tiles;
+            let mut chunks = HashSet::new();
+            for tile in expanded.iter().chain(&[start, goal]) {
+                for (step, _) in NEIGHBOURS {
+                    chunks.insert((*tile + step).div_euclid(size));
+                }
+                chunks.insert(tile.div_euclid(size));
+            }
+            for chunk in &chunks {
+                self.by_chunk.entry(*chunk).or_default().insert(key);
+            }
+            self.paths.insert(key, CachedPath { path, chunks });
+        }
+        self.paths[&key].path.as_deref()
+    }
+
+    /// Where to swim next to go from `from` to `to` (meters): the next tile of the path,
+    /// or `to` itself when close, without a path or out of budget.
+    pub fn next_waypoint(
+        &mut self,
+        tiles: &LoadedTiles,
+        cm: &ChunkManager,
+        from: Vec2,
+        to: Vec2,
+    ) -> Vec2 {
+        let (Some(start), Some(goal)) =
+            (nearest_water(tiles, cm, from), nearest_water(tiles, cm, to))
+        else {
+            return to;
+        };
+        match self.path(tiles, start, goal) {
+            Some([_, next, _, ..]) => cm.tile_to_world(*next),
+            _ => to,
+        }
+    }
+}
+
+/// The closest water tile among the corners of the cell under `pos`.
+fn nearest_water(tiles: &LoadedTiles, cm: &ChunkManager, pos: Vec2) -> Option<IVec2> {
+    let cell = (pos / cm.tile_size_in_meters).floor().as_ivec2();
+    let mut corners = [
+        cell,
+        cell + ivec2(1, 0),
+        cell + ivec2(0, 1),
+        cell + ivec2(1, 1),
+    ];
+    corners.sort_by(|a, b| {
+        let da = cm.tile_to_world(*a).distance_squared(pos);
+        let db = cm.tile_to_world(*b).distance_squared(pos);
+        da.total_cmp(&db)
+    });
+    corners.into_iter().find(|tile| tiles.is_water(*tile))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: i32 = 8;
+
+    fn water_matrix() -> Matrix<u32> {
+        Matrix::new(SIZE as usize + 1, SIZE as usize + 1, 0)
+    }
+
+    fn loaded<'a>(chunks: &[(IVec2, &'a Matrix<u32>)]) -> LoadedTiles<'a> {
+        LoadedTiles {
+            matrices: chunks.iter().copied().collect(),
+            chunk_size_in_tiles: IVec2::splat(SIZE),
+        }
+    }
+
+    #[test]
+    fn path_crosses_chunk_border() {
+        let (left, right) = (water_matrix(), water_matrix());
+        let tiles = loaded(&[(ivec2(0, 0), &left), (ivec2(1, 0), &right)]);
+
+        let (path, _) = find_path(&tiles, ivec2(2, 3), ivec2(12, 3), 1000);
+        let path = path.unwrap();
+        assert_eq!(path.first(), Some(&ivec2(2, 3)));
+        assert_eq!(path.last(), Some(&ivec2(12, 3)));
+    }
+
+    #[test]
+    fn path_never_cuts_corners() {
+        let mut matrix = water_matrix();
+        matrix[(1, 0)] = 1;
+        let tiles = loaded(&[(ivec2(0, 0), &matrix)]);
+
+        let (path, _) = find_path(&tiles, ivec2(0, 0), ivec2(1, 1), 1000);
+        assert_eq!(path.unwrap(), vec![ivec2(0, 0), ivec2(0, 1), ivec2(1, 1)]);
+
+        // Walled in on both sides, the diagonal alone doesn't lead out
+        matrix[(0, 1)] = 1;
+        let tiles = loaded(&[(ivec2(0, 0), &matrix)]);
+        assert!(
+            find_path(&tiles, ivec2(0, 0), ivec2(1, 1), 1000)
+                .0
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn no_path_into_unloaded_chunk() {
+        let matrix = water_matrix();
+        let tiles = loaded(&[(ivec2(0, 0), &matrix)]);
+
+        assert!(
+            find_path(&tiles, ivec2(2, 3), ivec2(12, 3), 1000)
+                .0
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn invalidate_chunk_drops_only_its_paths() {
+        let chunks: Vec<Matrix<u32>> = (0..3).map(|_| water_matrix()).collect();
+        let tiles = loaded(&[
+            (ivec2(0, 0), &chunks[0]),
+            (ivec2(1, 0), &chunks[1]),
+            (ivec2(2, 0), &chunks[2]),
+        ]);
+        let mut cache = PathCache::default();
+        let near = (ivec2(3, 3), ivec2(4, 4));
+        let far = (ivec2(19, 3), ivec2(20, 4));
+        assert!(cache.path(&tiles, near.0, near.1).is_some());
+        assert!(cache.path(&tiles, far.0, far.1).is_some());
+
+        cache.invalidate_chunk(ivec2(2, 0));
+        assert!(cache.paths.contains_key(&near));
+        assert!(!cache.paths.contains_key(&far));
+    }
+}
@@ -1,6 +1,8 @@
 use legion::Resources;
 use rapier2d::prelude::*;
 
+use super::physics_events::PhysicsEvents;
+
 pub struct PhysicsContext {
     pub gravity: Vector<f32>,
     pub pipeline: PhysicsPipeline,
@@ -37,4 +39,5 @@ impl PhysicsContext {
 
 pub fn init_physics(resources: &mut Resources) {
     resources.insert(PhysicsContext::new(vector![0.0, 9.8]));
+    resources.insert(PhysicsEvents::default());
 }
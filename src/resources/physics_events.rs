@@ -0,0 +1,142 @@
+use std::collections::{HashMap, HashSet};
+
+use legion::Entity;
+use macroquad::math::Vec2;
+use rapier2d::{
+    crossbeam::channel::{Receiver, unbounded},
+    prelude::{
+        ChannelEventCollector, ColliderHandle, CollisionEvent, CollisionEventFlags,
+        ContactForceEvent,
+    },
+};
+
+/// Two colliders and the entities that own them. An entity is `None` if its collider
+/// has no owner, e.g. it belongs to nothing the ECS knows of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionPair {
+    pub colliders: (ColliderHandle, ColliderHandle),
+    pub entities: (Option<Entity>, Option<Entity>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhysicsEvent {
+    /// Two colliders started touching, or overlapping if one is a sensor.
+    CollisionStarted { pair: CollisionPair, sensor: bool },
+    /// They stopped; `removed` if it was because one of them was removed.
+    CollisionStopped {
+        pair: CollisionPair,
+        sensor: bool,
+        removed: bool,
+    },
+    /// Two colliders pushed each other harder than `CONTACT_FORCE_THRESHOLD`.
+    ContactForce {
+        pair: CollisionPair,
+        force: Vec2,
+        magnitude: f32,
+    },
+}
+
+/// Contact forces under it (in newtons) aren't reported, so resting bodies stay quiet.
+pub const CONTACT_FORCE_THRESHOLD: f32 = 1.0;
+
+/// The events of the last physics step, gathered by a `ChannelEventCollector` and
+/// mapped to entities. Also keeps which colliders are touching right now.
+pub struct PhysicsEvents {
+    collector: ChannelEventCollector,
+    collisions: Receiver<CollisionEvent>,
+    forces: Receiver<ContactForceEvent>,
+    owners: HashMap<ColliderHandle, Entity>,
+    /// The owners of the step before, for the colliders removed since.
+    previous_owners: HashMap<ColliderHandle, Entity>,
+    touching: HashSet<(ColliderHandle, ColliderHandle)>,
+    pub events: Vec<PhysicsEvent>,
+}
+
+impl Default for PhysicsEvents {
+    fn default() -> Self {
+        let (collision_send, collisions) = unbounded();
+        let (force_send, forces) = unbounded();
+        Self {
+            collector: ChannelEventCollector::new(collision_send, force_send),
+            collisions,
+            forces,
+            owners: HashMap::new(),
+            previous_owners: HashMap::new(),
+            touching: HashSet::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl PhysicsEvents {
+    /// The event handler to give to `PhysicsPipeline::step`.
+    pub fn collector(&self) -> &ChannelEventCollector {
+        &self.collector
+    }
+
+    /// Who owns each collider, set before every step.
+    pub fn set_owners(&mut self, owners: HashMap<ColliderHandle, Entity>) {
+        self.previous_owners = std::mem::replace(&mut self.owners, owners);
+    }
+
+    pub fn owner(&self, collider: ColliderHandle) -> Option<Entity> {
+        self.owners
+            .get(&collider)
+            .or_else(|| self.previous_owners.get(&collider))
+            .copied()
+    }
+
+    /// Replaces the events with the ones the last step sent.
+    pub fn collect(&mut self) {
+        self.events.clear();
+        while let Ok(event) = self.collisions.try_recv() {
+            let (a, b) = (event.collider1(), event.collider2());
+            let pair = self.pair(a, b);
+            match event {
+                CollisionEvent::Started(_, _, flags) => {
+                    self.touching.insert(ordered(a, b));
+                    self.events.push(PhysicsEvent::CollisionStarted {
+                        pair,
+                        sensor: flags.contains(CollisionEventFlags::SENSOR),
+                    });
+                }
+                CollisionEvent::Stopped(_, _, flags) => {
+                    self.touching.remove(&ordered(a, b));
+                    self.events.push(PhysicsEvent::CollisionStopped {
+                        pair,
+                        sensor: flags.contains(CollisionEventFlags::SENSOR),
+                        removed: flags.contains(CollisionEventFlags::REMOVED),
+                    });
+                }
+            }
+        }
+        while let Ok(event) = self.forces.try_recv() {
+            self.events.push(PhysicsEvent::ContactForce {
+                pair: self.pair(event.collider1, event.collider2),
+                force: Vec2::new(event.total_force.x, event.total_force.y),
+                magnitude: event.total_force_magnitude,
+            });
+        }
+    }
+
+    /// The pairs of colliders touching (or overlapping) after the last step.
+    pub fn touching(&self) -> impl Iterator<Item = CollisionPair> + '_ {
+        self.touching.iter().map(|&(a, b)| self.pair(a, b))
+    }
+
+    //=====PRIVATE======
+    fn pair(&self, a: ColliderHandle, b: ColliderHandle) -> CollisionPair {
+        CollisionPair {
+            colliders: (a, b),
+            entities: (self.owner(a), self.owner(b)),
+        }
+    }
+}
+
+fn ordered(a: ColliderHandle, b: ColliderHandle) -> (ColliderHandle, ColliderHandle) {
+    if a.into_raw_parts() <= b.into_raw_parts() {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
@@ -27,6 +27,11 @@ pub fn calculate_dst(position: Vec2, size: Vec2, scale: Vec2) -> Rect {
 pub trait Renderable: Debug {
     fn z_order(&self) -> f32;
     fn render(&self, transform: &Transform, textures: &Textures);
+
+    /// Where it's drawn, in world pixels, for culling. `None` is always drawn.
+    fn bounds(&self, _transform: &Transform, _textures: &Textures) -> Option<Rect> {
+        None
+    }
 }
 
 impl Renderable for Sprite {
@@ -62,6 +67,15 @@ impl Renderable for Sprite {
             },
         );
     }
+
+    fn bounds(&self, transform: &Transform, textures: &Textures) -> Option<Rect> {
+        let texture = textures.0.get(self.image_path.as_str())?;
+        Some(calculate_dst(
+            transform.position,
+            Vec2::new(texture.width(), texture.height()) / METERS_TO_PIXELS,
+            transform.scale,
+        ))
+    }
 }
 
 impl Renderable for (&Spritesheet, &AnimationPlayer) {
@@ -103,6 +117,14 @@ impl Renderable for (&Spritesheet, &AnimationPlayer) {
             },
         );
     }
+
+    fn bounds(&self, transform: &Transform, _: &Textures) -> Option<Rect> {
+        Some(calculate_dst(
+            transform.position,
+            self.0.dst_size,
+            transform.scale,
+        ))
+    }
 }
 
 impl Renderable for (&TileMap, &TileMapSource) {
@@ -111,98 +133,89 @@ impl Renderable for (&TileMap, &TileMapSource) {
     }
 
     fn render(&self, transform: &Transform, textures: &Textures) {
-        let tilemap = self.0;
-        let source = self.1;
-        for y in 0..source.matrix.len() {
-            for x in 0..source.matrix[0].len() {
-                let tile_id = source.matrix[y as usize][x as usize];
-                let world_x =
-                    x as f32 * tilemap.tile_size.x * transform.scale.x + transform.position.x;
-                let world_y =
-                    y as f32 * tilemap.tile_size.y * transform.scale.y + transform.position.y;
+        let (tilemap, source) = *self;
+        let texture = textures
+            .0
+            .get(&tilemap.tileset_path)
+            .expect("Tileset não carregada");
+        let size = tilemap.tile_size * transform.scale * METERS_TO_PIXELS;
+        let origin = transform.position * METERS_TO_PIXELS;
+
+        for (y, row) in source.matrix.iter().enumerate() {
+            for (x, tile_id) in row.iter().enumerate() {
+                // 0 is an empty tile
+                if *tile_id == 0 {
+                    continue;
+                }
                 let src = tilemap
                     .tiles
-                    .get(&tile_id)
+                    .get(tile_id)
                     .expect("Algum tile não corresponde aos Tiles conhecidos");
                 let src_rect = Rect::new(
-                    (src.x as f32 * tilemap.tile_size.x) as f32,
-                    (src.y as f32 * tilemap.tile_size.y) as f32,
-                    tilemap.tile_size.x as f32,
-                    tilemap.tile_size.y as f32,
+                    src.x as f32 * tilemap.tile_size_in_tileset.x,
+                    src.y as f32 * tilemap.tile_size_in_tileset.y,
+                    tilemap.tile_size_in_tileset.x,
+                    tilemap.tile_size_in_tileset.y,
                 );
+                let dest = origin + vec2(x as f32, y as f32) * size;
                 draw_texture_ex(
-                    textures
-                        .0
-                        .get(&tilemap.tileset_path)
-                        .expect("Tileset não carregada"),
-                    world_x,
-                    world_y,
+                    texture,
+                    dest.x,
+                    dest.y,
                     WHITE,
                     DrawTextureParams {
                         source: Some(src_rect),
-                        dest_size: Some(vec2(
-                            tilemap.tile_size.x * transform.scale.x,
-                            tilemap.tile_size.y * transform.scale.y,
-                        )),
+                        dest_size: Some(size),
                         ..Default::default()
                     },
                 );
             }
         }
     }
+
+    fn bounds(&self, transform: &Transform, _: &Textures) -> Option<Rect> {
+        let rows = self.1.matrix.len();
+        let cols = self.1.matrix.first().map_or(0, |row| row.len());
+        let size =
+            vec2(cols as f32, rows as f32) * self.0.tile_size * transform.scale * METERS_TO_PIXELS;
+        let origin = transform.position * METERS_TO_PIXELS;
+        Some(Rect::new(origin.x, origin.y, size.x, size.y))
+    }
 }
 
-impl Renderable for (&TileMap, &Chunk) {
+impl Renderable for (&TileMap, &ChunkBake) {
     fn z_order(&self) -> f32 {
         self.0.z_order
     }
 
-    fn render(&self, transform: &Transform, textures: &Textures) {
-        let tilemap = self.0;
-        let source = self.1;
-        if source.matrix.is_none() {
+    fn render(&self, transform: &Transform, _: &Textures) {
+        let (tilemap, bake) = *self;
+        let Some(target) = &bake.target else {
             return;
-        }
-        let matrix = source.matrix.as_ref().unwrap();
-        for y in 0..matrix.height {
-            for x in 0..matrix.width {
-                let tile_id = matrix.get(x, y).unwrap();
-                let screen_x =
-                    x as f32 * tilemap.tile_size.x * METERS_TO_PIXELS * transform.scale.x;
-                //+ transform.position.x;
-
-                let screen_y =
-                    y as f32 * tilemap.tile_size.y * METERS_TO_PIXELS * transform.scale.y;
-                //+ transform.position.y;
-                let src = tilemap
-                    .tiles
-                    .get(tile_id)
-                    .expect("Algum tile não corresponde aos Tiles conhecidos");
-                let src_rect = Rect::new(
-                    (src.x as f32 * tilemap.tile_size_in_tileset.x) as f32,
-                    (src.y as f32 * tilemap.tile_size_in_tileset.y) as f32,
-                    tilemap.tile_size_in_tileset.x as f32,
-                    tilemap.tile_size_in_tileset.y as f32,
-                );
-                draw_texture_ex(
-                    textures
-                        .0
-                        .get(&tilemap.tileset_path)
-                        .expect("Tileset não carregada"),
-                    screen_x + (transform.position.x * METERS_TO_PIXELS),
-                    screen_y + (transform.position.y * METERS_TO_PIXELS),
-                    WHITE,
-                    DrawTextureParams {
-                        source: Some(src_rect),
-                        dest_size: Some(vec2(
-                            tilemap.tile_size.x * METERS_TO_PIXELS * transform.scale.x,
-                            tilemap.tile_size.y * METERS_TO_PIXELS * transform.scale.y,
-                        )),
-                        ..Default::default()
-                    },
-                );
-            }
-        }
+        };
+        let texture = &target.texture;
+        let tiles = vec2(texture.width(), texture.height()) / tilemap.tile_size_in_tileset;
+
+        draw_texture_ex(
+            texture,
+            transform.position.x * METERS_TO_PIXELS,
+            transform.position.y * METERS_TO_PIXELS,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(tiles * tilemap.tile_size * METERS_TO_PIXELS * transform.scale),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn bounds(&self, transform: &Transform, _: &Textures) -> Option<Rect> {
+        let (tilemap, bake) = *self;
+        // Nothing is drawn before the first bake, so nothing to cull
+        let texture = &bake.target.as_ref()?.texture;
+        let tiles = vec2(texture.width(), texture.height()) / tilemap.tile_size_in_tileset;
+        let size = tiles * tilemap.tile_size * METERS_TO_PIXELS * transform.scale;
+        let pos = transform.position * METERS_TO_PIXELS;
+        Some(Rect::new(pos.x, pos.y, size.x, size.y))
     }
 }
 
@@ -225,6 +238,14 @@ impl Renderable for DebugSprite {
             },
         );
     }
+
+    fn bounds(&self, transform: &Transform, _: &Textures) -> Option<Rect> {
+        Some(calculate_dst(
+            transform.position,
+            self.size,
+            transform.scale,
+        ))
+    }
 }
 
 impl Renderable for Body {
@@ -246,6 +267,14 @@ impl Renderable for Body {
             },
         );
     }
+
+    fn bounds(&self, transform: &Transform, _: &Textures) -> Option<Rect> {
+        Some(calculate_dst(
+            transform.position,
+            self.size,
+            transform.scale * 2.,
+        ))
+    }
 }
 
 impl Renderable for WeaponHolder {
@@ -270,4 +299,10 @@ impl Renderable for Bullet {
         let dst = calculate_dst(transform.position, Vec2::ONE * 0.05, Vec2::ONE);
         draw_circle(dst.x, dst.y, dst.size().x, colors::GREEN);
     }
+
+    fn bounds(&self, transform: &Transform, _: &Textures) -> Option<Rect> {
+        let dst = calculate_dst(transform.position, Vec2::ONE * 0.05, Vec2::ONE);
+        let r = dst.size().x;
+        Some(Rect::new(dst.x - r, dst.y - r, r * 2., r * 2.))
+    }
 }
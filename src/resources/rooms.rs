@@ -0,0 +1,262 @@
+use std::path::Path;
+
+use macroquad::math::{IVec2, UVec2, Vec2, ivec2};
+use tiled::{LayerType, Loader, PropertyValue, TileLayer};
+
+use crate::common::{
+    Matrix,
+    rng::{RngStream, chunk_rng},
+};
+
+/// Something a room brings besides its tiles, from the room's object layers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RoomObject {
+    Item {
+        item: String,
+        image_path: String,
+        quantity: u8,
+    },
+    Monster,
+    SpawnPoint {
+        kind: String,
+    },
+    /// See `AirSource`.
+    Air {
+        radius: f32,
+        rate: f32,
+    },
+}
+
+impl RoomObject {
+    /// Reads a Tiled object by its type (or class): `item` (properties `item`, `image`
+    /// and `quantity`), `monster`, `spawn` (property `kind`) or `air` (properties `radius`
+    /// and `rate`). `None` for other types.
+    pub fn from_tiled(object: &tiled::ObjectData) -> Option<Self> {
+        let string = |name: &str| match object.properties.get(name) {
+            Some(PropertyValue::StringValue(s) | PropertyValue::FileValue(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let float = |name: &str, default: f32| match object.properties.get(name) {
+            Some(PropertyValue::FloatValue(f)) => *f,
+            _ => default,
+        };
+        match object.user_type.as_str() {
+            "item" => Some(RoomObject::Item {
+                item: string("item").unwrap_or_else(|| object.name.clone()),
+                image_path: string("image").unwrap_or_default(),
+                quantity: match object.properties.get("quantity") {
+                    Some(PropertyValue::IntValue(q)) => (*q).clamp(1, 255) as u8,
+                    _ => 1,
+                },
+            }),
+            "monster" => Some(RoomObject::Monster),
+            "spawn" => Some(RoomObject::SpawnPoint {
+                kind: string("kind").unwrap_or_default(),
+            }),
+            "air" => Some(RoomObject::Air {
+                radius: float("radius", 0.3),
+                rate: float("rate", 20.0),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// A hand-made piece of cave, authored in Tiled.
+///
+/// The tile layer named `rock` (or the first tile layer) gives the tiles: any tile is
+/// rock, no tile is water. Objects are read by `RoomObject::from_tiled`.
+#[derive(Debug, Clone)]
+pub struct Room {
+    pub name: String,
+    pub tiles: Matrix<u32>,
+    /// Positions in tiles from the room's top left corner.
+    pub objects: Vec<(Vec2, RoomObject)>,
+}
+
+impl Room {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let map = Loader::new()
+            .load_tmx_map(path)
+            .map_err(|e| format!("Erro lendo {}: {}", path.display(), e))?;
+        let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+
+        let rock = map
+            .layers()
+            .filter_map(|layer| match layer.layer_type() {
+                LayerType::Tiles(tiles) => Some((layer.name == "rock", tiles)),
+                _ => None,
+            })
+            .max_by_key(|(is_rock, _)| *is_rock)
+            .map(|(_, tiles)| tiles);
+        let Some(TileLayer::Finite(rock)) = rock else {
+            return Err(format!("{}: sem camada de tiles finita", path.display()));
+        };
+
+        let mut tiles = Matrix::new(rock.width() as usize, rock.height() as usize, 0);
+        for y in 0..tiles.height {
+            for x in 0..tiles.width {
+                if rock.get_tile(x as i32, y as i32).is_some() {
+                    tiles[(x, y)] = 1;
+                }
+            }
+        }
+
+        let mut objects = Vec::new();
+        for layer in map.layers() {
+            let LayerType::Objects(layer) = layer.layer_type() else {
+                continue;
+            };
+            for object in layer.objects() {
+                match RoomObject::from_tiled(&object) {
+                    Some(room_object) => {
+                        objects.push((Vec2::new(object.x, object.y) / tile_size, room_object))
+                    }
+                    None => eprintln!(
+                        "{}: objeto de tipo desconhecido: {}",
+                        path.display(),
+                        object.user_type
+                    ),
+                }
+            }
+        }
+
+        Ok(Self {
+            name: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            tiles,
+            objects,
+        })
+    }
+
+    pub fn size(&self) -> IVec2 {
+        ivec2(self.tiles.width as i32, self.tiles.height as i32)
+    }
+}
+
+/// Where a room may appear: each chunk in the depth range (chunk rows) rolls `chance`
+/// to be the anchor of one.
+#[derive(Debug, Clone)]
+pub struct RoomRule {
+    pub room: Room,
+    pub chance: f32,
+    pub min_depth: i32,
+    pub max_depth: i32,
+}
+
+/// Places rooms in the world. A chunk anchors at most one room (the first rule that
+/// rolls it), somewhere inside the chunk, and the room may spill into the neighbours.
+/// It only depends on the seed and the anchor chunk, so every chunk that a room
+/// touches finds the same placement.
+pub struct Rooms {
+    pub rules: Vec<RoomRule>,
+    pub seed: u64,
+    pub chunk_size_in_tiles: UVec2,
+    /// World tile areas `(min, max)`, `max` exclusive, kept for scenes: no room is
+    /// placed over them and `RoomStamp` leaves them as open water.
+    pub reserved: Vec<(IVec2, IVec2)>,
+}
+
+impl Rooms {
+    pub fn new(seed: u64, chunk_size_in_tiles: UVec2, rules: Vec<RoomRule>) -> Self {
+        Self {
+            rules,
+            seed,
+            chunk_size_in_tiles,
+            reserved: Vec::new(),
+        }
+    }
+
+    pub fn reserve(&mut self, min: IVec2, max: IVec2) {
+        self.reserved.push((min, max));
+    }
+
+    /// The reserved areas that touch the world tiles from `min` (inclusive) to `max`
+    /// (exclusive).
+    pub fn reserved_in(&self, min: IVec2, max: IVec2) -> impl Iterator<Item = (IVec2, IVec2)> {
+        self.reserved
+            .iter()
+            .copied()
+            .filter(move |&(r_min, r_max)| overlaps(min, max, r_min, r_max))
+    }
+
+    /// The room anchored by a chunk, with the world tile of its top left corner.
+    pub fn placement(&self, anchor: IVec2) -> Option<(&Room, IVec2)> {
+        let rng = chunk_rng(self.seed, anchor, RngStream::Rooms);
+        let size = self.chunk_size_in_tiles.as_ivec2();
+
+        for rule in &self.rules {
+            if anchor.y < rule.min_depth || anchor.y > rule.max_depth {
+                continue;
+            }
+            if rng.gen_range(0.0f32, 1.0f32) < rule.chance {
+                let offset = ivec2(rng.gen_range(0, size.x), rng.gen_range(0, size.y));
+                let origin = anchor * size + offset;
+                if self
+                    .reserved_in(origin, origin + rule.room.size())
+                    .next()
+                    .is_some()
+                {
+                    return None;
+                }
+                return Some((&rule.room, origin));
+            }
+        }
+        None
+    }
+
+    /// Every room that touches the world tiles from `min` (inclusive) to `max` (exclusive).
+    pub fn placements_in(&self, min: IVec2, max: IVec2) -> Vec<(&Room, IVec2)> {
+        let Some(biggest) = self.rules.iter().map(|r| r.room.size()).reduce(IVec2::max) else {
+            return Vec::new();
+        };
+        let size = self.chunk_size_in_tiles.as_ivec2();
+        let first = (min - biggest).div_euclid(size);
+        let last = (max - 1).div_euclid(size);
+
+        let mut out = Vec::new();
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                if let Some((room, origin)) = self.placement(ivec2(x, y))
+                    && overlaps(origin, origin + room.size(), min, max)
+                {
+                    out.push((room, origin));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// A shipwreck near the surface and a treasure vault deeper down.
+pub fn default_room_rules() -> Vec<RoomRule> {
+    let rules = [
+        ("assets/rooms/shipwreck.tmx", 0.05, 0, 6),
+        ("assets/rooms/vault.tmx", 0.04, 8, 40),
+    ];
+
+    rules
+        .into_iter()
+        .filter_map(
+            |(path, chance, min_depth, max_depth)| match Room::load(path) {
+                Ok(room) => Some(RoomRule {
+                    room,
+                    chance,
+                    min_depth,
+                    max_depth,
+                }),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+fn overlaps(a_min: IVec2, a_max: IVec2, b_min: IVec2, b_max: IVec2) -> bool {
+    a_min.x < b_max.x && a_min.y < b_max.y && a_max.x > b_min.x && a_max.y > b_min.y
+}
@@ -0,0 +1,258 @@
+use std::{fs, path::Path};
+
+use macroquad::rand::RandGenerator;
+use serde::Deserialize;
+
+use crate::{
+    common::Matrix,
+    comps::{Behaviour, LootDrop},
+};
+
+/// Where in the cave an entry may appear, judged from the water tile and its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Placement {
+    /// Water all around (the 8 neighbours).
+    OpenWater,
+    /// Rock right below.
+    OnFloor,
+    /// Rock to the left or to the right.
+    OnWall,
+    /// Rock on at least three sides.
+    InCavity,
+}
+
+impl Placement {
+    /// Whether the water tile `(x, y)` of `matrix` fits. Tiles on the border of the
+    /// matrix never fit, their neighbours being unknown.
+    pub fn fits(self, matrix: &Matrix<u32>, x: usize, y: usize) -> bool {
+        if x == 0 || y == 0 || x + 1 >= matrix.width || y + 1 >= matrix.height {
+            return false;
+        }
+        if matrix[(x, y)] != 0 {
+            return false;
+        }
+        let solid = |dx: isize, dy: isize| {
+            matrix[(x.wrapping_add_signed(dx), y.wrapping_add_signed(dy))] != 0
+        };
+
+        match self {
+            Placement::OpenWater => (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .all(|(dx, dy)| !solid(dx, dy)),
+            Placement::OnFloor => solid(0, 1),
+            Placement::OnWall => solid(-1, 0) || solid(1, 0),
+            Placement::InCavity => {
+                [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                    .into_iter()
+                    .filter(|&(dx, dy)| solid(dx, dy))
+                    .count()
+                    >= 3
+            }
+        }
+    }
+}
+
+/// The entity an entry creates.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum SpawnTemplate {
+    Item {
+        item: String,
+        image_path: String,
+        #[serde(default = "one")]
+        quantity: u8,
+    },
+    /// With the default behaviours if none are given, dropping `loot` on death.
+    Monster {
+        #[serde(default)]
+        behaviours: Vec<Behaviour>,
+        #[serde(default)]
+        loot: Vec<LootDrop>,
+    },
+    /// `count` fish swimming together (see `Fish`).
+    School { count: u32 },
+    /// An air pocket or a bubble vent (see `AirSource`).
+    Air { radius: f32, rate: f32 },
+}
+
+fn one() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnEntry {
+    pub weight: f32,
+    /// Depth range in chunks (chunk rows), inclusive. Open if not given.
+    #[serde(default)]
+    pub min_depth: Option<f32>,
+    #[serde(default)]
+    pub max_depth: Option<f32>,
+    /// Biome names (see `Biome::name`) where it may appear. Anywhere if empty.
+    #[serde(default)]
+    pub biomes: Vec<String>,
+    pub placement: Placement,
+    pub spawn: SpawnTemplate,
+}
+
+impl SpawnEntry {
+    pub fn allows(&self, depth: f32, biome: &str) -> bool {
+        self.min_depth.is_none_or(|min| depth >= min)
+            && self.max_depth.is_none_or(|max| depth <= max)
+            && (self.biomes.is_empty() || self.biomes.iter().any(|b| b == biome))
+    }
+}
+
+/// Each chunk makes `attempts` tries: a random tile, rolled against `chance`, then one
+/// of the entries allowed there, by weight. At most `cap` entities per chunk.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpawnTable {
+    pub name: String,
+    pub attempts: u32,
+    pub chance: f32,
+    pub cap: u32,
+    pub entries: Vec<SpawnEntry>,
+}
+
+impl SpawnTable {
+    /// A weighted pick among the entries that pass `allowed`.
+    pub fn pick(
+        &self,
+        rng: &RandGenerator,
+        allowed: impl Fn(&SpawnEntry) -> bool,
+    ) -> Option<&SpawnEntry> {
+        let candidates: Vec<&SpawnEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.weight > 0.0 && allowed(e))
+            .collect();
+        let total: f32 = candidates.iter().map(|e| e.weight).sum();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0.0, total);
+        for entry in &candidates {
+            if roll < entry.weight {
+                return Some(entry);
+            }
+            roll -= entry.weight;
+        }
+        candidates.last().copied()
+    }
+}
+
+/// What populates the chunks, read from a RON file (see `assets/spawns.ron`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SpawnTables {
+    pub tables: Vec<SpawnTable>,
+}
+
+impl SpawnTables {
+    /// The images of the items the tables may create, loot included.
+    pub fn image_paths(&self) -> impl Iterator<Item = &String> {
+        self.tables
+            .iter()
+            .flat_map(|table| &table.entries)
+            .flat_map(|entry| match &entry.spawn {
+                SpawnTemplate::Item { image_path, .. } => vec![image_path],
+                SpawnTemplate::Monster { loot, .. } => {
+                    loot.iter().map(|drop| &drop.image_path).collect()
+                }
+                _ => vec![],
+            })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Erro lendo {}: {}", path.display(), e))?;
+        ron::from_str(&text).map_err(|e| format!("Erro lendo {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::inventory::ItemDef;
+
+    /// A matrix from rows of `#` (rock) and `.` (water).
+    fn matrix(rows: &[&str]) -> Matrix<u32> {
+        let mut matrix = Matrix::new(rows[0].len(), rows.len(), 0);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                matrix[(x, y)] = (c == '#') as u32;
+            }
+        }
+        matrix
+    }
+
+    fn entry(min_depth: Option<f32>, max_depth: Option<f32>, biomes: &[&str]) -> SpawnEntry {
+        SpawnEntry {
+            weight: 1.0,
+            min_depth,
+            max_depth,
+            biomes: biomes.iter().map(|b| b.to_string()).collect(),
+            placement: Placement::OpenWater,
+            spawn: SpawnTemplate::Item {
+                item: "Tanque de Ar".into(),
+                image_path: "assets/tank.png".into(),
+                quantity: 1,
+            },
+        }
+    }
+
+    #[test]
+    fn spawns_asset_loads_with_known_items() {
+        let tables = SpawnTables::load("assets/spawns.ron").unwrap();
+        assert!(!tables.tables.is_empty());
+
+        for entry in tables.tables.iter().flat_map(|table| &table.entries) {
+            let items: Vec<&String> = match &entry.spawn {
+                SpawnTemplate::Item { item, .. } => vec![item],
+                SpawnTemplate::Monster { loot, .. } => loot.iter().map(|d| &d.item).collect(),
+                _ => vec![],
+            };
+            for item in items {
+                assert!(
+                    ItemDef::by_name(item).is_some(),
+                    "item desconhecido: {}",
+                    item
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn placement_fits() {
+        let m = matrix(&[
+            "#####", //
+            "#...#", "#...#", "#.#.#", "#####",
+        ]);
+        assert!(!Placement::OpenWater.fits(&m, 2, 1));
+        assert!(Placement::OnFloor.fits(&m, 2, 2));
+        assert!(!Placement::OnFloor.fits(&m, 1, 1));
+        assert!(Placement::OnWall.fits(&m, 1, 2));
+        assert!(!Placement::OnWall.fits(&m, 2, 2));
+        assert!(Placement::InCavity.fits(&m, 1, 3));
+        assert!(!Placement::InCavity.fits(&m, 2, 1));
+        //Rock and the border never fit
+        assert!(!Placement::OnFloor.fits(&m, 2, 3));
+        assert!(!Placement::OnWall.fits(&m, 0, 2));
+
+        let open = matrix(&["...", "...", "..."]);
+        assert!(Placement::OpenWater.fits(&open, 1, 1));
+    }
+
+    #[test]
+    fn entry_allows() {
+        let open = entry(None, None, &[]);
+        assert!(open.allows(0.0, "recife"));
+        assert!(open.allows(100.0, "abismo"));
+
+        let ranged = entry(Some(4.0), Some(8.0), &["abismo"]);
+        assert!(ranged.allows(4.0, "abismo"));
+        assert!(ranged.allows(8.0, "abismo"));
+        assert!(!ranged.allows(3.9, "abismo"));
+        assert!(!ranged.allows(8.1, "abismo"));
+        assert!(!ranged.allows(6.0, "recife"));
+    }
+}
@@ -0,0 +1,46 @@
+use macroquad::math::{IVec2, Vec2};
+
+use super::chunk_manager::ChunkManager;
+
+/// A tile change, in world tile coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileEdit {
+    pub tile: IVec2,
+    pub value: u32,
+}
+
+/// Queue of tile changes (mining, explosions, debug painting...).
+/// Anyone can push here; `apply_tile_edits_system` updates the chunks and their colliders.
+#[derive(Default)]
+pub struct TileEdits {
+    pub edits: Vec<TileEdit>,
+}
+
+impl TileEdits {
+    /// Sets the tile under `world_pos` (in meters). Use 0 to clear it.
+    pub fn set(&mut self, world_pos: Vec2, value: u32, cm: &ChunkManager) {
+        self.edits.push(TileEdit {
+            tile: cm.world_to_tile(world_pos),
+            value,
+        });
+    }
+
+    /// Sets every tile inside the circle.
+    pub fn fill_circle(&mut self, center: Vec2, radius: f32, value: u32, cm: &ChunkManager) {
+        let min = cm.world_to_tile(center - Vec2::splat(radius));
+        let max = cm.world_to_tile(center + Vec2::splat(radius));
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let tile = IVec2::new(x, y);
+                if cm.tile_to_world(tile).distance(center) <= radius {
+                    self.edits.push(TileEdit { tile, value });
+                }
+            }
+        }
+    }
+
+    /// Drain all pending edits for processing.
+    pub fn drain(&mut self) -> Vec<TileEdit> {
+        std::mem::take(&mut self.edits)
+    }
+}
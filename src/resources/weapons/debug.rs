@@ -5,7 +5,9 @@ use macroquad::{math::Vec2, time::get_frame_time};
 use rapier2d::prelude::RigidBodyHandle;
 
 use crate::{
-    comps::{Body, Counter, Sprite, Transform, Weapon, WeaponHolder},
+    comps::{
+        Body, CollisionLayer, Counter, Damage, Faction, Sprite, Transform, Weapon, WeaponHolder,
+    },
     resources::{physics::PhysicsContext, renderable::Renderable},
 };
 
@@ -91,7 +93,10 @@ impl Weapon for DebugGun {
         let rigid_bodies = &mut physics.bodies;
         let colliders = &mut physics.colliders;
 
-        let mut b = Body::new(Vec2::ONE * 0.05, true);
+        let mut b = Body::new(Vec2::ONE * 0.05, true)
+            .with_layer(CollisionLayer::Projectile)
+            .with_buoyancy(1.0)
+            .with_drag(0.3, 0.3);
         let mut t = Transform {
             position: ctx.position,
             ..Default::default()
@@ -110,6 +115,11 @@ impl Weapon for DebugGun {
             },
             Counter { times: 0 },
             b,
+            Damage {
+                amount: 10.,
+                faction: Faction::Player,
+                consumed_on_hit: true,
+            },
         ));
 
         let mut new_weapon = ctx.weapon_holder.weapon.as_ref().unwrap().box_clone();
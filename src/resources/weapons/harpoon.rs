@@ -1,47 +1,45 @@
 use std::any::Any;
 
-use legion::{
-    Entity,
-    query::*,
-    systems::CommandBuffer,
-    world::{self, SubWorld},
-};
+use legion::{Entity, query::*, systems::CommandBuffer, world::SubWorld};
 use macroquad::{
     color::{Color, GRAY, WHITE},
-    math::{Vec2, vec2},
+    math::Vec2,
+    shapes::draw_line,
     texture::{DrawTextureParams, draw_texture_ex},
+    time::get_frame_time,
 };
-use nalgebra::{UnitComplex, vector};
+use nalgebra::{Isometry2, vector};
 use rapier2d::prelude::{
-    ColliderBuilder, ColliderHandle, NarrowPhase, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+    ColliderHandle, FixedJointBuilder, ImpulseJointHandle, RigidBodyHandle, RigidBodySet,
+    RopeJointBuilder,
 };
 
 use crate::{
-    comps::{Body, DebugSprite, Transform, Weapon, WeaponContext, WeaponHolder},
+    comps::{
+        Body, CollisionLayer, Damage, Faction, Monster, Transform, Weapon, WeaponContext,
+        WeaponHolder, WorldItem,
+    },
     resources::{
         METERS_TO_PIXELS,
+        physics::PhysicsContext,
         renderable::{Renderable, calculate_dst},
     },
 };
 
-pub fn surface_type_to_bit(s: SurfaceType) -> u128 {
-    s as u128
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum BladeStatus {
+    /// In the gun, its body disabled.
     Sleeping,
+    /// Flying on the rope, until it hits something or `RUN_TIME` passes.
     Running,
+    /// Stuck in a wall, a monster or an item, the rope holding the diver like a grapple.
     Fixed,
+    /// Stuck and being reeled in: the diver goes to the wall, or what was hit comes to
+    /// the diver.
+    Reeling,
+    /// Loose and coming back to the gun.
+    Returning,
 }
-#[derive(Debug)]
-pub enum SurfaceType {
-    None,
-    Wall,
-    Monster,
-    Item,
-}
-
 #[derive(Debug, Clone)]
 enum SurfaceHit {
     Wall,
@@ -55,6 +53,15 @@ pub struct Harpoon {
     blade_status: BladeStatus,
     last_hit: Option<SurfaceHit>,
     blade_entity: Option<Entity>,
+    /// From the diver to the blade, while the blade is out.
+    rope: Option<ImpulseJointHandle>,
+    /// From the blade to the monster or item it's stuck in.
+    anchor: Option<ImpulseJointHandle>,
+    /// Seconds since the blade was shot, while running.
+    running_time: f32,
+    /// Where the blade is, for drawing it with the line.
+    blade_position: Vec2,
+    blade_rotation: f32,
 }
 
 impl Weapon for Harpoon {
@@ -89,7 +96,10 @@ impl Weapon for Harpoon {
         let bodies = &mut ph.bodies;
         let colliders = &mut ph.colliders;
 
-        let mut blade_b = Body::new(Vec2::ONE * 0.5, true);
+        let mut blade_b = Body::new(BLADE_SIZE, true)
+            .with_layer(CollisionLayer::Projectile)
+            .with_buoyancy(1.0)
+            .with_drag(0.3, 1.5);
         let mut blade_t = Transform {
             position: ctx.position,
             rotation: ctx.rotation,
@@ -101,18 +111,23 @@ impl Weapon for Harpoon {
             bodies,
             colliders,
         );
+        // Asleep in the gun until shot
+        if let Some(rb) = blade_b.body_handle.and_then(|h| bodies.get_mut(h)) {
+            rb.set_enabled(false);
+        }
         let blade = cb.push((
             blade_t,
             blade_b,
-            DebugSprite {
-                z_order: 40.,
-                color: GRAY,
-                size: Vec2::ONE * 0.5,
+            Damage {
+                amount: 15.,
+                faction: Faction::Player,
+                consumed_on_hit: false,
             },
         ));
         let mut nu = self._get_weapon_clone(ctx.weapon_holder);
         self._modify_clone(
             |h| {
+                *h = Harpoon::default();
                 h.blade_entity = Some(blade);
                 h.active = true;
             },
@@ -126,20 +141,84 @@ impl Weapon for Harpoon {
             eprintln!("Erro> Harpoon exige acesso ao sistema de física");
             return;
         };
-        if !self.blade_entity.is_some() {
+        let Some((blade_handle, blade_col)) = self._get_blade_handles(ctx.world) else {
             eprintln!("Erro> Harpoon não possui entidade de lâmina");
             return;
-        }
-        let narrow = &ph.narrow_phase;
-        if self._is_blade_colliding(narrow, ctx.world) {
-            self._fix(&mut ctx, cb);
+        };
+        let Some(player_handle) = ctx.player_body.body_handle else {
+            return;
+        };
+        if self.blade_status == BladeStatus::Sleeping {
+            return;
         }
 
+        let world = ctx.world;
+        let dt = get_frame_time();
+        let player_pos = body_position(&ph.bodies, player_handle);
+        let blade_pos = body_position(&ph.bodies, blade_handle);
+        let distance = player_pos.distance(blade_pos);
+
+        let mut nu = self.clone();
         match self.blade_status {
-            BladeStatus::Sleeping => self._step_sleeping(&mut ctx),
-            BladeStatus::Running => {}
-            BladeStatus::Fixed => {}
+            BladeStatus::Sleeping => {}
+            BladeStatus::Running => {
+                nu.running_time += dt;
+                if let Some(hit) = self._find_hit(ph, world, blade_col, blade_pos) {
+                    nu._fix(ph, world, blade_handle, hit);
+                } else if nu.running_time >= RUN_TIME {
+                    nu._start_returning(ph, blade_handle, blade_col);
+                }
+            }
+            BladeStatus::Fixed => {
+                if !self._is_hit_alive(ph, world) {
+                    nu._start_returning(ph, blade_handle, blade_col);
+                }
+            }
+            BladeStatus::Reeling => {
+                if !self._is_hit_alive(ph, world) {
+                    nu._start_returning(ph, blade_handle, blade_col);
+                } else if distance <= CATCH_DISTANCE {
+                    nu._stow(ph, blade_handle);
+                } else {
+                    nu._shorten_rope(ph, distance - REEL_SPEED * dt);
+                    match &self.last_hit {
+                        Some(SurfaceHit::Wall) => self._apply_retract_force_wall(
+                            &mut ph.bodies,
+                            player_handle,
+                            blade_pos,
+                        ),
+                        Some(SurfaceHit::Monster(entity)) | Some(SurfaceHit::Item(entity)) => self
+                            ._apply_retract_force_entity(
+                                &mut ph.bodies,
+                                world,
+                                player_pos,
+                                blade_pos,
+                                *entity,
+                            ),
+                        None => {}
+                    }
+                }
+            }
+            BladeStatus::Returning => {
+                if distance <= CATCH_DISTANCE {
+                    nu._stow(ph, blade_handle);
+                } else {
+                    nu._shorten_rope(ph, distance);
+                    if let Some(rb) = ph.bodies.get_mut(blade_handle) {
+                        let velocity = (player_pos - blade_pos) / distance * RETURN_SPEED;
+                        rb.set_linvel(vector![velocity.x, velocity.y], true);
+                    }
+                }
+            }
         }
+        nu.blade_position = blade_pos;
+        nu.blade_rotation = body_rotation(&ph.bodies, blade_handle);
+        self._register_changes(
+            Box::new(nu),
+            cb,
+            ctx.weapon_holder_entity,
+            ctx.weapon_holder,
+        );
     }
 
     fn shoot(&self, cb: &mut CommandBuffer, mut ctx: WeaponContext) {
@@ -150,13 +229,29 @@ impl Weapon for Harpoon {
 
         match self.blade_status {
             BladeStatus::Sleeping => self._shoot(&mut ctx, cb),
-            BladeStatus::Running | BladeStatus::Fixed => self._retract(&mut ctx),
+            BladeStatus::Fixed => self._reel(&mut ctx, cb),
+            BladeStatus::Running | BladeStatus::Reeling => self._retract(&mut ctx, cb),
+            BladeStatus::Returning => {}
         }
     }
 
-    fn exit(&self, cb: &mut CommandBuffer, ctx: WeaponContext) {
+    fn exit(&self, cb: &mut CommandBuffer, mut ctx: WeaponContext) {
+        if let (Some(ph), Some(blade)) = (ctx.physics.as_mut(), self.blade_entity) {
+            if let Some((blade_handle, _)) = self._get_blade_handles(ctx.world) {
+                // Takes the rope and the anchor with it
+                ph.bodies.remove(
+                    blade_handle,
+                    &mut ph.islands,
+                    &mut ph.colliders,
+                    &mut ph.impulse_joints,
+                    &mut ph.multibody_joints,
+                    true,
+                );
+            }
+            cb.remove(blade);
+        }
         let mut nu = self._get_weapon_clone(ctx.weapon_holder);
-        self._modify_clone(|h| h.active = false, &mut nu);
+        self._modify_clone(|h| *h = Harpoon::default(), &mut nu);
         self._register_changes(nu, cb, ctx.weapon_holder_entity, ctx.weapon_holder);
     }
 }
@@ -168,13 +263,18 @@ impl Default for Harpoon {
             blade_entity: None,
             active: false,
             last_hit: None,
+            rope: None,
+            anchor: None,
+            running_time: 0.,
+            blade_position: Vec2::ZERO,
+            blade_rotation: 0.,
         }
     }
 }
 
 impl Renderable for Harpoon {
     fn z_order(&self) -> f32 {
-        0.0
+        40.
     }
     // this transform, in this case will be from the player
     fn render(&self, transform: &Transform, textures: &crate::resources::Textures) {
@@ -207,30 +307,58 @@ impl Renderable for Harpoon {
                 ..Default::default()
             },
         );
+        if self.blade_status == BladeStatus::Sleeping {
+            return;
+        }
+
         //DRAW LINE
+        let from = weapon_transform.position * METERS_TO_PIXELS;
+        let to = self.blade_position * METERS_TO_PIXELS;
+        draw_line(from.x, from.y, to.x, to.y, 1.5, ROPE_COLOR);
 
         //DRAW BLADE
+        let half = Vec2::from_angle(self.blade_rotation) * BLADE_SIZE.x * METERS_TO_PIXELS;
+        let (tip, tail) = (to + half, to - half);
+        draw_line(tail.x, tail.y, tip.x, tip.y, BLADE_SIZE.y * 2. * METERS_TO_PIXELS, GRAY);
     }
 }
 
-const SHOOT_FORCE: f32 = 10.;
-impl Harpoon {
-    fn _get_blade_handle(&self, world: &SubWorld<'_>) -> RigidBodyHandle {
-        let mut query = <&Body>::query();
-        let body = query
-            .get(world, self.blade_entity.expect("entity does not exist"))
-            .expect("Não existe essa entidade");
-
-        body.body_handle.expect("O body não possui handle")
-    }
+/// Half extents of the blade body.
+const BLADE_SIZE: Vec2 = Vec2::new(0.12, 0.03);
+const SHOOT_SPEED: f32 = 9.;
+/// The longest the rope gets, in meters.
+const ROPE_LENGTH: f32 = 5.;
+/// Seconds a running blade flies before coming back with nothing.
+const RUN_TIME: f32 = 0.8;
+/// Meters of rope reeled in per second.
+const REEL_SPEED: f32 = 4.;
+/// Acceleration of whatever is being reeled in, in m/s².
+const REEL_FORCE: f32 = 12.;
+const RETURN_SPEED: f32 = 10.;
+/// How close to the diver the blade goes back in the gun.
+const CATCH_DISTANCE: f32 = 0.4;
+/// How close to an item the running blade hooks it (they don't collide).
+const HOOK_RADIUS: f32 = 0.2;
+const ROPE_COLOR: Color = Color::new(0.85, 0.8, 0.65, 1.0);
+
+fn body_position(bodies: &RigidBodySet, handle: RigidBodyHandle) -> Vec2 {
+    bodies
+        .get(handle)
+        .map(|rb| Vec2::new(rb.translation().x, rb.translation().y))
+        .unwrap_or_default()
+}
 
-    fn _get_blade_col_handle(&self, world: &SubWorld<'_>) -> ColliderHandle {
-        let mut query = <&Body>::query();
-        let body = query
-            .get(world, self.blade_entity.expect("entity does not exist"))
-            .expect("Não existe essa entidade");
+fn body_rotation(bodies: &RigidBodySet, handle: RigidBodyHandle) -> f32 {
+    bodies
+        .get(handle)
+        .map(|rb| rb.rotation().angle())
+        .unwrap_or_default()
+}
 
-        body.collider_handle.expect("O body não possui handle")
+impl Harpoon {
+    fn _get_blade_handles(&self, world: &SubWorld<'_>) -> Option<(RigidBodyHandle, ColliderHandle)> {
+        let body = <&Body>::query().get(world, self.blade_entity?).ok()?;
+        Some((body.body_handle?, body.collider_handle?))
     }
 
     fn _get_weapon_clone(&self, weapon_holder: &WeaponHolder) -> Box<dyn Weapon> {
@@ -255,119 +383,224 @@ impl Harpoon {
         );
     }
 
-    fn _step_sleeping(&self, ctx: &mut WeaponContext) {
-        if self.blade_entity == None {
-            return;
+    /// What the running blade touches: a wall or monster it collides with, or an item
+    /// close enough.
+    fn _find_hit(
+        &self,
+        ph: &PhysicsContext,
+        world: &SubWorld,
+        blade_col: ColliderHandle,
+        blade_pos: Vec2,
+    ) -> Option<SurfaceHit> {
+        for contact_pair in ph.narrow_phase.contact_pairs_with(blade_col) {
+            if !contact_pair.has_any_active_contact {
+                continue;
+            }
+            let other = if contact_pair.collider1 == blade_col {
+                contact_pair.collider2
+            } else {
+                contact_pair.collider1
+            };
+            let Some(other_collider) = ph.colliders.get(other) else {
+                continue;
+            };
+            match CollisionLayer::of(other_collider) {
+                Some(CollisionLayer::Wall) => return Some(SurfaceHit::Wall),
+                Some(CollisionLayer::Monster) => {
+                    let monster = <(Entity, &Body, &Monster)>::query()
+                        .iter(world)
+                        .find(|(_, body, _)| body.collider_handle == Some(other))
+                        .map(|(e, _, _)| *e);
+                    if let Some(monster) = monster {
+                        return Some(SurfaceHit::Monster(monster));
+                    }
+                }
+                _ => {}
+            }
         }
 
-        let world = ctx.world;
-        let ph = ctx.physics.as_mut().unwrap();
-        let bodies = &mut ph.bodies;
-        if let Some(rb) = bodies.get_mut(self._get_blade_handle(world)) {
-            rb.set_rotation(UnitComplex::from_angle(ctx.rotation), true);
-        }
+        <(Entity, &Transform, &WorldItem)>::query()
+            .iter(world)
+            .find(|(_, t, item)| item.active && t.position.distance(blade_pos) <= HOOK_RADIUS)
+            .map(|(e, _, _)| SurfaceHit::Item(*e))
     }
 
-    fn _is_blade_colliding(&self, narrow: &NarrowPhase, world: &SubWorld) -> bool {
-        narrow
-            .contact_pairs_with(self._get_blade_col_handle(world))
-            .next()
-            .is_some()
+    /// Whether what the blade is stuck in is still there.
+    fn _is_hit_alive(&self, ph: &PhysicsContext, world: &SubWorld) -> bool {
+        match &self.last_hit {
+            Some(SurfaceHit::Monster(entity)) | Some(SurfaceHit::Item(entity)) => {
+                <&Body>::query()
+                    .get(world, *entity)
+                    .ok()
+                    .and_then(|body| body.body_handle)
+                    .is_some_and(|handle| ph.bodies.contains(handle))
+            }
+            _ => true,
+        }
     }
 
     fn _shoot(&self, ctx: &mut WeaponContext, cb: &mut CommandBuffer) {
-        if self.blade_entity == None {
+        let Some((blade_handle, blade_col)) = self._get_blade_handles(ctx.world) else {
             return;
+        };
+        let Some(player_handle) = ctx.player_body.body_handle else {
+            return;
+        };
+        let ph = ctx.physics.as_mut().unwrap();
+
+        if let Some(collider) = ph.colliders.get_mut(blade_col) {
+            collider.set_enabled(true);
+        }
+        let direction = Vec2::from_angle(ctx.rotation);
+        let player_velocity = ph
+            .bodies
+            .get(player_handle)
+            .map(|rb| Vec2::new(rb.linvel().x, rb.linvel().y))
+            .unwrap_or_default();
+        if let Some(rb) = ph.bodies.get_mut(blade_handle) {
+            rb.set_enabled(true);
+            rb.set_position(
+                Isometry2::new(vector![ctx.position.x, ctx.position.y], ctx.rotation),
+                true,
+            );
+            let velocity = direction * SHOOT_SPEED + player_velocity;
+            rb.set_linvel(vector![velocity.x, velocity.y], true);
+            rb.set_angvel(0., true);
         }
+        let rope = ph.impulse_joints.insert(
+            player_handle,
+            blade_handle,
+            RopeJointBuilder::new(ROPE_LENGTH).contacts_enabled(false),
+            true,
+        );
 
         let mut nu = self._get_weapon_clone(ctx.weapon_holder);
         self._modify_clone(
             |harpoon| {
                 harpoon.blade_status = BladeStatus::Running;
+                harpoon.rope = Some(rope);
+                harpoon.running_time = 0.;
+                harpoon.blade_position = ctx.position;
+                harpoon.blade_rotation = ctx.rotation;
             },
             &mut nu,
         );
         self._register_changes(nu, cb, ctx.weapon_holder_entity, ctx.weapon_holder);
+    }
 
-        let ph = ctx.physics.as_mut().unwrap();
-        let world = ctx.world;
-        let bodies = &mut ph.bodies;
-        if let Some(rb) = bodies.get_mut(self._get_blade_handle(world)) {
-            let target = ctx.position - (Vec2::from_angle(ctx.rotation) * SHOOT_FORCE);
-            rb.add_force(vector![target.x, target.y], true);
-        }
+    fn _reel(&self, ctx: &mut WeaponContext, cb: &mut CommandBuffer) {
+        let mut nu = self._get_weapon_clone(ctx.weapon_holder);
+        self._modify_clone(|h| h.blade_status = BladeStatus::Reeling, &mut nu);
+        self._register_changes(nu, cb, ctx.weapon_holder_entity, ctx.weapon_holder);
     }
 
-    fn _retract(&self, ctx: &mut WeaponContext) {
-        if self.blade_entity == None {
+    /// Lets go of whatever the blade is in and brings it back.
+    fn _retract(&self, ctx: &mut WeaponContext, cb: &mut CommandBuffer) {
+        let Some((blade_handle, blade_col)) = self._get_blade_handles(ctx.world) else {
             return;
-        }
-
+        };
         let ph = ctx.physics.as_mut().unwrap();
-        let world = ctx.world;
-        let bodies = &mut ph.bodies;
-        let player_handle = ctx.player_body.body_handle;
 
-        let blade_pos = if let Some(rb) = bodies.get_mut(self._get_blade_handle(world)) {
-            rb.lock_translations(false, true);
-            let p = rb.position().translation;
-            vec2(p.x, p.y)
-        } else {
-            vec2(0., 0.)
-        };
+        let mut nu = self.clone();
+        nu._start_returning(ph, blade_handle, blade_col);
+        self._register_changes(
+            Box::new(nu),
+            cb,
+            ctx.weapon_holder_entity,
+            ctx.weapon_holder,
+        );
+    }
 
-        if let Some(hit) = &self.last_hit {
-            match hit {
-                SurfaceHit::Wall => {
-                    self._apply_retract_force_wall(bodies, player_handle.unwrap(), blade_pos)
+    /// Sticks the blade where it hit: held in place in a wall, jointed to a monster or
+    /// an item.
+    fn _fix(
+        &mut self,
+        ph: &mut PhysicsContext,
+        world: &SubWorld,
+        blade_handle: RigidBodyHandle,
+        hit: SurfaceHit,
+    ) {
+        match &hit {
+            SurfaceHit::Wall => {
+                if let Some(rb) = ph.bodies.get_mut(blade_handle) {
+                    rb.set_linvel(vector![0., 0.], true);
+                    rb.set_angvel(0., true);
+                    rb.lock_translations(true, true);
+                    rb.lock_rotations(true, true);
                 }
-
-                _ => {}
+            }
+            SurfaceHit::Monster(entity) | SurfaceHit::Item(entity) => {
+                let Some(target) = <&Body>::query()
+                    .get(world, *entity)
+                    .ok()
+                    .and_then(|body| body.body_handle)
+                else {
+                    return;
+                };
+                let (Some(blade), Some(other)) =
+                    (ph.bodies.get(blade_handle), ph.bodies.get(target))
+                else {
+                    return;
+                };
+                // Where the blade is now, seen from the target
+                let frame = other.position().inv_mul(blade.position());
+                let joint = FixedJointBuilder::new()
+                    .local_frame2(frame)
+                    .contacts_enabled(false);
+                self.anchor = Some(ph.impulse_joints.insert(blade_handle, target, joint, true));
             }
         }
+        self.last_hit = Some(hit);
+        self.blade_status = BladeStatus::Fixed;
     }
 
-    fn _fix(&self, ctx: &mut WeaponContext, cb: &mut CommandBuffer) {
-        if self.blade_entity == None {
-            return;
+    /// Frees the blade from what it's stuck in.
+    fn _detach(&mut self, ph: &mut PhysicsContext, blade_handle: RigidBodyHandle) {
+        if let Some(anchor) = self.anchor.take() {
+            ph.impulse_joints.remove(anchor, true);
+        }
+        if let Some(rb) = ph.bodies.get_mut(blade_handle) {
+            rb.lock_translations(false, true);
+            rb.lock_rotations(false, true);
         }
+        self.last_hit = None;
+    }
 
-        let ph = ctx.physics.as_mut().unwrap();
-        let world = ctx.world;
-        let bodies = &mut ph.bodies;
-        let colliders = &ph.colliders;
-        let narrow = &ph.narrow_phase;
+    /// Lets go and flies back through everything, so it can't get caught on the way.
+    fn _start_returning(
+        &mut self,
+        ph: &mut PhysicsContext,
+        blade_handle: RigidBodyHandle,
+        blade_col: ColliderHandle,
+    ) {
+        self._detach(ph, blade_handle);
+        if let Some(collider) = ph.colliders.get_mut(blade_col) {
+            collider.set_enabled(false);
+        }
+        self.blade_status = BladeStatus::Returning;
+    }
 
-        if let Some(rb) = bodies.get_mut(self._get_blade_handle(world)) {
-            rb.lock_translations(true, true);
+    /// Puts the blade back in the gun.
+    fn _stow(&mut self, ph: &mut PhysicsContext, blade_handle: RigidBodyHandle) {
+        self._detach(ph, blade_handle);
+        if let Some(rope) = self.rope.take() {
+            ph.impulse_joints.remove(rope, true);
+        }
+        if let Some(rb) = ph.bodies.get_mut(blade_handle) {
+            rb.set_linvel(vector![0., 0.], false);
+            rb.set_enabled(false);
         }
+        self.blade_status = BladeStatus::Sleeping;
+        self.running_time = 0.;
+    }
 
-        for contact_pair in narrow.contact_pairs_with(self._get_blade_col_handle(world)) {
-            let other = if contact_pair.collider1 == self._get_blade_col_handle(world) {
-                contact_pair.collider2
-            } else {
-                contact_pair.collider1
-            };
-            let Some(other_collider) = colliders.get(other) else {
-                continue;
-            };
-            match other_collider.user_data {
-                0 => continue,
-                1 => {
-                    let mut nu = self._get_weapon_clone(ctx.weapon_holder);
-                    self._modify_clone(
-                        |h| {
-                            h.last_hit = Some(SurfaceHit::Wall);
-                        },
-                        &mut nu,
-                    );
-                    self._register_changes(nu, cb, ctx.weapon_holder_entity, ctx.weapon_holder);
-                    break;
-                }
-                _ => {
-                    //TODO: Detect monsters and items
-                }
-            }
+    fn _shorten_rope(&self, ph: &mut PhysicsContext, length: f32) {
+        let Some(joint) = self.rope.and_then(|rope| ph.impulse_joints.get_mut(rope, true)) else {
+            return;
+        };
+        if let Some(rope) = joint.data.as_rope_mut() {
+            let length = length.clamp(CATCH_DISTANCE, rope.max_distance());
+            rope.set_max_distance(length);
         }
     }
 
@@ -378,17 +611,30 @@ impl Harpoon {
         blade_pos: Vec2,
     ) {
         if let Some(rb) = bodies.get_mut(player_handle) {
-            rb.add_force(vector![blade_pos.x, blade_pos.y], true);
+            let p = rb.translation();
+            let pull = (blade_pos - Vec2::new(p.x, p.y)).normalize_or_zero() * REEL_FORCE * rb.mass();
+            rb.add_force(vector![pull.x, pull.y], true);
         }
     }
 
     fn _apply_retract_force_entity(
         &self,
         bodies: &mut RigidBodySet,
-        player_handle: RigidBodyHandle,
+        world: &SubWorld,
+        player_pos: Vec2,
         blade_pos: Vec2,
         entity: Entity,
     ) {
-        //TODO
+        let Some(handle) = <&Body>::query()
+            .get(world, entity)
+            .ok()
+            .and_then(|body| body.body_handle)
+        else {
+            return;
+        };
+        if let Some(rb) = bodies.get_mut(handle) {
+            let pull = (player_pos - blade_pos).normalize_or_zero() * REEL_FORCE * rb.mass();
+            rb.add_force(vector![pull.x, pull.y], true);
+        }
     }
 }
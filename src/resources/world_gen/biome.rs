@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use fastnoise_lite::{FastNoiseLite, NoiseType};
+
+use crate::{
+    common::{Matrix, rng::noise_seed},
+    resources::biome::BiomeMap,
+};
+
+use super::{GenRegion, WorldGenerator};
+
+/// Like `NoiseThreshold`, but each biome brings its own noise scale and threshold.
+/// Near a border the `noise - threshold` of both biomes is mixed by their weight, so the
+/// caves change shape smoothly instead of being cut at a line.
+pub struct BiomeNoise {
+    pub biomes: Arc<BiomeMap>,
+    pub noise: FastNoiseLite,
+}
+
+impl BiomeNoise {
+    pub fn new(seed: u64, biomes: Arc<BiomeMap>) -> Self {
+        let mut noise = FastNoiseLite::new();
+        noise.set_seed(Some(noise_seed(seed)));
+        noise.set_noise_type(Some(NoiseType::Perlin));
+
+        Self { biomes, noise }
+    }
+}
+
+impl WorldGenerator for BiomeNoise {
+    fn generate(&self, region: &GenRegion, matrix: &mut Matrix<u32>) {
+        for y in 0..matrix.height {
+            for x in 0..matrix.width {
+                let world_pos = region.world_position(x, y);
+                let value: f32 = self
+                    .biomes
+                    .weights(world_pos)
+                    .iter()
+                    .map(|(biome, weight)| {
+                        let pos = world_pos * biome.noise_scale;
+                        let noise_val = self.noise.get_noise_2d(pos.x, pos.y);
+                        (noise_val - biome.threshold) * weight
+                    })
+                    .sum();
+                matrix[(x, y)] = if value < 0.0 { 0 } else { 1 };
+            }
+        }
+    }
+}
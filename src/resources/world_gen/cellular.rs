@@ -0,0 +1,79 @@
+use crate::common::{Matrix, rng::mix64};
+
+use super::{GenRegion, WorldGenerator};
+
+/// Cellular automata caves: a tile becomes solid with at least `birth` solid neighbours,
+/// and stays solid with at least `survival`.
+///
+/// With `fill`, it starts from random tiles (that chance of being solid). Without it, it
+/// smooths what the previous passes made.
+pub struct CellularAutomata {
+    pub fill: Option<f32>,
+    pub iterations: usize,
+    pub birth: u8,
+    pub survival: u8,
+}
+
+impl Default for CellularAutomata {
+    fn default() -> Self {
+        Self {
+            fill: Some(0.45),
+            iterations: 4,
+            birth: 5,
+            survival: 4,
+        }
+    }
+}
+
+impl CellularAutomata {
+    fn random_fill(&self, region: &GenRegion, matrix: &mut Matrix<u32>, fill: f32) {
+        for y in 0..matrix.height {
+            for x in 0..matrix.width {
+                let tile = region.world_tile(x, y);
+                let pos = ((tile.x as u32 as u64) << 32) | tile.y as u32 as u64;
+                let roll = (mix64(region.seed ^ mix64(pos)) >> 40) as f32 / (1u64 << 24) as f32;
+                matrix[(x, y)] = if roll < fill { 1 } else { 0 };
+            }
+        }
+    }
+
+    fn step(&self, matrix: &Matrix<u32>) -> Matrix<u32> {
+        let mut next = matrix.clone();
+        // The border has missing neighbours; it is inside the padding, so it's left as is
+        for y in 1..matrix.height.saturating_sub(1) {
+            for x in 1..matrix.width.saturating_sub(1) {
+                let mut neighbours = 0;
+                for ny in y - 1..=y + 1 {
+                    for nx in x - 1..=x + 1 {
+                        if (nx, ny) != (x, y) && matrix[(nx, ny)] != 0 {
+                            neighbours += 1;
+                        }
+                    }
+                }
+
+                let limit = if matrix[(x, y)] != 0 {
+                    self.survival
+                } else {
+                    self.birth
+                };
+                next[(x, y)] = if neighbours >= limit { 1 } else { 0 };
+            }
+        }
+        next
+    }
+}
+
+impl WorldGenerator for CellularAutomata {
+    fn padding(&self) -> usize {
+        self.iterations
+    }
+
+    fn generate(&self, region: &GenRegion, matrix: &mut Matrix<u32>) {
+        if let Some(fill) = self.fill {
+            self.random_fill(region, matrix, fill);
+        }
+        for _ in 0..self.iterations {
+            *matrix = self.step(matrix);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+mod biome;
+mod cellular;
+mod noise;
+mod pipeline;
+mod rooms;
+pub use biome::*;
+pub use cellular::*;
+pub use noise::*;
+pub use pipeline::*;
+pub use rooms::*;
+
+use std::sync::Arc;
+
+use macroquad::math::{IVec2, Vec2, uvec2};
+
+use crate::{common::Matrix, resources::biome::BiomeMap};
+
+/// Where the matrix being generated is in the world.
+pub struct GenRegion {
+    /// World tile of `matrix[(0, 0)]`.
+    pub origin: IVec2,
+    pub tile_size_in_meters: Vec2,
+    pub seed: u64,
+}
+
+impl GenRegion {
+    pub fn world_tile(&self, x: usize, y: usize) -> IVec2 {
+        self.origin + uvec2(x as u32, y as u32).as_ivec2()
+    }
+
+    pub fn world_position(&self, x: usize, y: usize) -> Vec2 {
+        self.world_tile(x, y).as_vec2() * self.tile_size_in_meters
+    }
+}
+
+/// A way to fill a chunk matrix (0 is water, anything else is solid).
+///
+/// Generators only look at world positions and the seed, never at the chunk, so chunks
+/// always match at their seams. Those that look at neighbour tiles (like cellular
+/// automata) ask for a `padding`: the matrix they get is bigger by that many tiles on
+/// each side, and only the middle is kept.
+pub trait WorldGenerator: Send + Sync {
+    fn padding(&self) -> usize {
+        0
+    }
+
+    fn generate(&self, region: &GenRegion, matrix: &mut Matrix<u32>);
+}
+
+/// Cave styles that can be picked with `--caves`. Only `biomes` and `smooth` follow the
+/// biome parameters, the others use the same caves at every depth.
+pub fn by_name(name: &str, seed: u64, biomes: &Arc<BiomeMap>) -> Option<Box<dyn WorldGenerator>> {
+    let biome_noise = || BiomeNoise::new(seed, biomes.clone());
+    match name {
+        "biomes" => Some(Box::new(biome_noise())),
+        "noise" => Some(Box::new(NoiseThreshold::new(seed, Vec2::ONE * 40.0, 0.01))),
+        "cellular" => Some(Box::new(CellularAutomata::default())),
+        "smooth" => Some(Box::new(Pipeline::default().then(biome_noise()).then(
+            CellularAutomata {
+                fill: None,
+                iterations: 2,
+                ..Default::default()
+            },
+        ))),
+        _ => None,
+    }
+}
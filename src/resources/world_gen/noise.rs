@@ -0,0 +1,39 @@
+use fastnoise_lite::{FastNoiseLite, NoiseType};
+use macroquad::math::Vec2;
+
+use crate::common::{Matrix, rng::noise_seed};
+
+use super::{GenRegion, WorldGenerator};
+
+/// Solid where a Perlin noise sample is over `threshold`.
+pub struct NoiseThreshold {
+    pub noise: FastNoiseLite,
+    pub noise_scale: Vec2,
+    pub threshold: f32,
+}
+
+impl NoiseThreshold {
+    pub fn new(seed: u64, noise_scale: Vec2, threshold: f32) -> Self {
+        let mut noise = FastNoiseLite::new();
+        noise.set_seed(Some(noise_seed(seed)));
+        noise.set_noise_type(Some(NoiseType::Perlin));
+
+        Self {
+            noise,
+            noise_scale,
+            threshold,
+        }
+    }
+}
+
+impl WorldGenerator for NoiseThreshold {
+    fn generate(&self, region: &GenRegion, matrix: &mut Matrix<u32>) {
+        for y in 0..matrix.height {
+            for x in 0..matrix.width {
+                let world_pos = region.world_position(x, y) * self.noise_scale;
+                let noise_val = self.noise.get_noise_2d(world_pos.x, world_pos.y);
+                matrix[(x, y)] = if noise_val < self.threshold { 0 } else { 1 };
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::common::Matrix;
+
+use super::{GenRegion, WorldGenerator};
+
+/// Runs each pass over the result of the previous one.
+#[derive(Default)]
+pub struct Pipeline {
+    pub passes: Vec<Box<dyn WorldGenerator>>,
+}
+
+impl Pipeline {
+    pub fn then(self, pass: impl WorldGenerator + 'static) -> Self {
+        self.then_boxed(Box::new(pass))
+    }
+
+    pub fn then_boxed(mut self, pass: Box<dyn WorldGenerator>) -> Self {
+        self.passes.push(pass);
+        self
+    }
+}
+
+impl WorldGenerator for Pipeline {
+    /// Each pass spoils its own padding, so the pipeline needs all of them.
+    fn padding(&self) -> usize {
+        self.passes.iter().map(|p| p.padding()).sum()
+    }
+
+    fn generate(&self, region: &GenRegion, matrix: &mut Matrix<u32>) {
+        for pass in &self.passes {
+            pass.generate(region, matrix);
+        }
+    }
+}
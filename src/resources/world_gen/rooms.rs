@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use macroquad::math::{IVec2, ivec2};
+
+use crate::{common::Matrix, resources::rooms::Rooms};
+
+use super::{GenRegion, WorldGenerator};
+
+/// Stamps the placed rooms over what the passes before it generated, then empties the
+/// areas reserved for scenes.
+pub struct RoomStamp {
+    pub rooms: Arc<Rooms>,
+}
+
+impl WorldGenerator for RoomStamp {
+    fn generate(&self, region: &GenRegion, matrix: &mut Matrix<u32>) {
+        let end = region.origin + ivec2(matrix.width as i32, matrix.height as i32);
+        for (room, origin) in self.rooms.placements_in(region.origin, end) {
+            for y in 0..room.tiles.height {
+                for x in 0..room.tiles.width {
+                    let local = origin + ivec2(x as i32, y as i32) - region.origin;
+                    if local.x >= 0 && local.y >= 0 {
+                        matrix.set(local.x as usize, local.y as usize, room.tiles[(x, y)]);
+                    }
+                }
+            }
+        }
+
+        for (min, max) in self.rooms.reserved_in(region.origin, end) {
+            let min = (min - region.origin).max(IVec2::ZERO);
+            let max = (max - region.origin).min(end - region.origin);
+            for y in min.y..max.y {
+                for x in min.x..max.x {
+                    matrix.set(x as usize, y as usize, 0);
+                }
+            }
+        }
+    }
+}
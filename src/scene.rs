@@ -0,0 +1,232 @@
+//! Fixed areas made in Tiled (a surface hub, a tutorial cave...), loaded as they are
+//! instead of being generated.
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use legion::World;
+use macroquad::math::{IVec2, Vec2, vec2};
+use tiled::{LayerType, Loader, ObjectShape, PropertyValue, TileLayer};
+
+use crate::{
+    common::shapes::greedy_rects_where,
+    comps::*,
+    resources::{chunk_manager::ChunkManager, inventory::ItemDef, rooms::RoomObject},
+    sys::chunk::get_chunk_by_position,
+};
+
+/// Pushes the entities of a `.tmx` map, with its top left corner at `origin` (meters).
+///
+/// - Each visible tile layer becomes a `TileMap` + `TileMapSource`, with the layer's
+///   `z_order` property (or its index) as z-order. Only its first tileset is used.
+/// - Layers named `collision` (or with a `collision` property set) become static
+///   `Body`s: tiles are merged into rectangles, rectangle objects are used as they are.
+/// - Other objects become entities, as in rooms (see `RoomObject::from_tiled`).
+pub fn load_scene(
+    world: &mut World,
+    path: impl AsRef<Path>,
+    origin: Vec2,
+    cm: &ChunkManager,
+) -> Result<(), String> {
+    let path = path.as_ref();
+    let map = Loader::new()
+        .load_tmx_map(path)
+        .map_err(|e| format!("Erro lendo {}: {}", path.display(), e))?;
+    let tile_size_in_tileset = vec2(map.tile_width as f32, map.tile_height as f32);
+    let tile_size = cm.tile_size_in_meters;
+
+    for (index, layer) in map.layers().enumerate() {
+        let z_order = match layer.properties.get("z_order") {
+            Some(PropertyValue::FloatValue(z)) => *z,
+            Some(PropertyValue::IntValue(z)) => *z as f32,
+            _ => index as f32,
+        };
+        let collision = layer.name == "collision"
+            || matches!(
+                layer.properties.get("collision"),
+                Some(PropertyValue::BoolValue(true))
+            );
+
+        match layer.layer_type() {
+            LayerType::Tiles(TileLayer::Finite(tiles)) => {
+                let (width, height) = (tiles.width() as usize, tiles.height() as usize);
+                let mut matrix = vec![vec![0; width]; height];
+                let mut tileset = None;
+                for (y, row) in matrix.iter_mut().enumerate() {
+                    for (x, id) in row.iter_mut().enumerate() {
+                        let Some(tile) = tiles.get_tile(x as i32, y as i32) else {
+                            continue;
+                        };
+                        let first = *tileset.get_or_insert(tile.tileset_index());
+                        if tile.tileset_index() != first {
+                            eprintln!(
+                                "{}: camada {} usa mais de uma tileset",
+                                path.display(),
+                                layer.name
+                            );
+                            continue;
+                        }
+                        // 0 is an empty tile
+                        *id = tile.id() + 1;
+                    }
+                }
+
+                if collision {
+                    let rects = greedy_rects_where(width, height, |x, y| matrix[y][x] != 0);
+                    for rect in rects {
+                        let pos = vec2(rect.x as f32, rect.y as f32) * tile_size;
+                        let size = vec2(rect.w as f32, rect.h as f32) * tile_size;
+                        push_wall(world, origin + pos, size);
+                    }
+                }
+
+                let Some(tileset) = tileset.map(|i| &map.tilesets()[i]) else {
+                    continue;
+                };
+                let Some(image) = &tileset.image else {
+                    eprintln!("{}: tileset {} sem imagem", path.display(), tileset.name);
+                    continue;
+                };
+                if !layer.visible {
+                    continue;
+                }
+
+                let columns = tileset.columns.max(1);
+                let used: HashMap<u32, IVec2> = matrix
+                    .iter()
+                    .flatten()
+                    .filter(|id| **id != 0)
+                    .map(|id| {
+                        let local = id - 1;
+                        (
+                            *id,
+                            IVec2::new((local % columns) as i32, (local / columns) as i32),
+                        )
+                    })
+                    .collect();
+
+                world.push((
+                    Transform {
+                        position: origin,
+                        ..Default::default()
+                    },
+                    TileMap {
+                        tileset_path: normalize(&image.source),
+                        tiles: used,
+                        tile_size,
+                        tile_size_in_tileset,
+                        z_order,
+                    },
+                    TileMapSource { matrix },
+                ));
+            }
+            LayerType::Tiles(TileLayer::Infinite(_)) => {
+                eprintln!(
+                    "{}: camada infinita {} não suportada",
+                    path.display(),
+                    layer.name
+                );
+            }
+            LayerType::Objects(objects) => {
+                for object in objects.objects() {
+                    let pos = origin + vec2(object.x, object.y) / tile_size_in_tileset * tile_size;
+                    if collision || object.user_type == "wall" {
+                        if let ObjectShape::Rect { width, height } = object.shape {
+                            let size = vec2(width, height) / tile_size_in_tileset * tile_size;
+                            push_wall(world, pos, size);
+                        }
+                        continue;
+                    }
+                    match RoomObject::from_tiled(&object) {
+                        Some(room_object) => push_object(world, pos, room_object, cm),
+                        None => eprintln!(
+                            "{}: objeto de tipo desconhecido: {}",
+                            path.display(),
+                            object.user_type
+                        ),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// The size of a `.tmx` map in tiles, to reserve its place before the world is generated
+/// (see `Rooms::reserve`).
+pub fn scene_size_in_tiles(path: impl AsRef<Path>) -> Result<IVec2, String> {
+    let path = path.as_ref();
+    let map = Loader::new()
+        .load_tmx_map(path)
+        .map_err(|e| format!("Erro lendo {}: {}", path.display(), e))?;
+    Ok(IVec2::new(map.width as i32, map.height as i32))
+}
+
+/// A static body covering the rectangle from `min` with `size` (meters).
+fn push_wall(world: &mut World, min: Vec2, size: Vec2) {
+    world.push((
+        Transform {
+            position: min + size / 2.,
+            ..Default::default()
+        },
+        Body::new(size / 2., false).with_layer(CollisionLayer::Wall),
+    ));
+}
+
+fn push_object(world: &mut World, position: Vec2, object: RoomObject, cm: &ChunkManager) {
+    let chunk = get_chunk_by_position(position, cm);
+    let transform = Transform {
+        position,
+        ..Default::default()
+    };
+
+    match object {
+        RoomObject::Item {
+            item,
+            image_path,
+            quantity,
+        } => match ItemDef::by_name(&item) {
+            Some(def) => {
+                world.push(WorldItem::bundle(
+                    position, def, quantity, image_path, chunk,
+                ));
+            }
+            None => eprintln!("Item desconhecido na cena: {}", item),
+        },
+        RoomObject::Monster => {
+            world.push(Monster::bundle(
+                position,
+                Brain::new(position, Vec::new()),
+                Loot::default(),
+                None,
+                chunk,
+            ));
+        }
+        RoomObject::SpawnPoint { kind } => {
+            world.push((transform, SpawnPoint { kind, chunk }));
+        }
+        RoomObject::Air { radius, rate } => {
+            world.push(AirSource::bundle(position, radius, rate, chunk));
+        }
+    }
+}
+
+/// Tiled gives image paths relative to the map (`assets/scenes/../tiles.png`), while
+/// textures are keyed by the plain path (`assets/tiles.png`).
+fn normalize(path: &Path) -> String {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir if out.file_name().is_some() => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out.to_string_lossy().replace('\\', "/")
+}
@@ -0,0 +1,64 @@
+use legion::{world::SubWorld, *};
+use macroquad::math::Vec2;
+use nalgebra::vector;
+
+use crate::{
+    comps::{Body, Brain, Chunk, Monster, Player, Senses, Transform},
+    resources::{
+        chunk_manager::ChunkManager,
+        navigation::{LoadedTiles, PathCache},
+        physics::PhysicsContext,
+    },
+};
+
+/// Steers the active monsters through their bodies, finding their way in the loaded
+/// chunks. Monsters of unloaded chunks are put to sleep, so they stay where they were
+/// left.
+#[system]
+#[read_component(Player)]
+#[read_component(Transform)]
+#[read_component(Monster)]
+#[read_component(Body)]
+#[read_component(Chunk)]
+#[write_component(Brain)]
+pub fn think(
+    world: &mut SubWorld,
+    #[resource] pc: &mut PhysicsContext,
+    #[resource] cm: &ChunkManager,
+    #[resource] paths: &mut PathCache,
+) {
+    paths.begin_frame();
+    let (mut brains, world) = world.split::<&mut Brain>();
+
+    let player = <(&Transform, &Player)>::query()
+        .iter(&world)
+        .next()
+        .map(|(t, _)| t.position);
+    let tiles = LoadedTiles::new(<&Chunk>::query().iter(&world), cm.chunk_size_in_tiles);
+
+    let mut q = <(Entity, &Transform, &Monster, &Body)>::query();
+    for (entity, transform, monster, body) in q.iter(&world) {
+        let Some(rb) = body.body_handle.and_then(|h| pc.bodies.get_mut(h)) else {
+            continue;
+        };
+        let Ok(brain) = <&mut Brain>::query().get_mut(&mut brains, *entity) else {
+            continue;
+        };
+
+        if !monster.active {
+            rb.set_linvel(vector![0.0, 0.0], false);
+            rb.sleep();
+            continue;
+        }
+
+        let velocity = Vec2::new(rb.linvel().x, rb.linvel().y);
+        let acceleration = brain.steer(&mut Senses {
+            position: transform.position,
+            velocity,
+            player,
+            route: &mut |from, to| paths.next_waypoint(&tiles, cm, from, to),
+        });
+        let force = acceleration * rb.mass();
+        rb.add_force(vector![force.x, force.y], true);
+    }
+}
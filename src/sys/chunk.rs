@@ -1,16 +1,17 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::RangeInclusive};
 
 use crate::{
     comps::*,
     resources::{
-        chunk_manager::ChunkManager, ivec2_to_vec2, physics::PhysicsContext, vec2_to_ivec2,
+        chunk_jobs::ChunkJobs, chunk_manager::ChunkManager, chunk_store::ChunkStore, ivec2_to_vec2,
+        navigation::PathCache, physics::PhysicsContext, tile_edits::TileEdits, vec2_to_ivec2,
     },
 };
 use legion::query::*;
 use legion::{Entity, system, systems::CommandBuffer, world::SubWorld};
-use macroquad::math::{IVec2, Rect, Vec2, ivec2};
+use macroquad::math::{IVec2, Rect, Vec2};
 
-fn get_chunk_by_position(pos: Vec2, cm: &ChunkManager) -> IVec2 {
+pub fn get_chunk_by_position(pos: Vec2, cm: &ChunkManager) -> IVec2 {
     let chunk_m = cm.chunk_size_in_meters;
     vec2_to_ivec2((pos / chunk_m).floor())
 }
@@ -38,14 +39,18 @@ pub fn debug_chunk(c: &Chunk) {
 
 #[system(for_each)]
 pub fn update_monster_chunk(t: &Transform, m: &mut Monster, #[resource] cm: &ChunkManager) {
-    m.chunk = get_chunk_by_position(t.position, cm)
+    if m.active {
+        m.chunk = get_chunk_by_position(t.position, cm)
+    }
 }
 
-fn get_chunk_tiles() -> HashMap<u32, IVec2> {
-    let mut cu = HashMap::new();
-    cu.insert(0, ivec2(0, 0));
-    cu.insert(1, ivec2(5, 5));
-    cu
+/// Items move (they can be dropped or pushed around), so their chunk must follow them to
+/// be saved with the right chunk when it's freed.
+#[system(for_each)]
+pub fn update_item_chunk(t: &Transform, i: &mut WorldItem, #[resource] cm: &ChunkManager) {
+    if i.active {
+        i.chunk = get_chunk_by_position(t.position, cm)
+    }
 }
 
 #[system]
@@ -65,20 +70,23 @@ pub fn create_new_chunks(
                 && !cm.chunks.contains_key(&pos)
             {
                 let rect = get_chunk_rect(pos, cm);
+                // The tileset can't change inside a chunk, so it follows the chunk center
+                let biome = cm.biomes.dominant(rect.center());
                 cm.chunks.insert(
                     pos,
                     cb.push((
                         Chunk::new(pos, rect),
-                        ChunkBody::new(cm.chunk_size_in_tiles, pos),
+                        ChunkBody::new(pos),
+                        ChunkBake::default(),
                         Transform {
                             position: get_world_position_by_chunk(pos, cm),
                             ..Default::default()
                         },
                         TileMap {
-                            tileset_path: "assets/dungeon_tiles.png".to_string(),
+                            tileset_path: biome.tileset_path.clone(),
                             tile_size: cm.tile_size_in_meters,
-                            tile_size_in_tileset: Vec2::new(8., 8.),
-                            tiles: get_chunk_tiles(),
+                            tile_size_in_tileset: biome.tile_size_in_tileset,
+                            tiles: biome.tiles.clone(),
                             z_order: 0.,
                         },
                     )),
@@ -88,23 +96,45 @@ pub fn create_new_chunks(
     }
 }
 
+/// Asks the workers for the matrices of the chunks in range, and loads the ones that
+/// are ready (nearest first, up to the jobs budget).
 #[system]
 #[read_component(Chunk)]
 #[read_component(Monster)]
 #[read_component(crate::comps::WorldItem)]
+#[read_component(Fish)]
 pub fn load_chunks(
     world: &SubWorld,
     #[resource] cm: &ChunkManager,
+    #[resource] store: &mut ChunkStore,
+    #[resource] jobs: &mut ChunkJobs,
+    #[resource] paths: &mut PathCache,
     cb: &mut CommandBuffer,
 ) {
-    let chunks_to_load: Vec<_> = <(Entity, &Chunk)>::query()
+    jobs.update(cm.player_chunk, cm.unloading_distance);
+
+    let chunks_to_load: Vec<_> = <&Chunk>::query()
         .iter(world)
-        .filter(|(_, chunk)| chunk.pos.distance_squared(cm.player_chunk) < cm.unloading_distance)
-        .filter(|(_, chunk)| chunk.state != ChunkState::Loaded)
+        .filter(|chunk| chunk.pos.distance_squared(cm.player_chunk) < cm.unloading_distance)
+        .filter(|chunk| chunk.state != ChunkState::Loaded)
+        .map(|chunk| chunk.pos)
         .collect();
+    for pos in chunks_to_load {
+        jobs.request(pos);
+    }
 
-    for (entity, chunk) in chunks_to_load {
-        chunk.load(entity, world, cm, cb);
+    for (pos, matrix) in jobs.take_ready(cm.player_chunk) {
+        // The chunk may have been freed, or went out of range, while it was generated
+        if pos.distance_squared(cm.player_chunk) >= cm.unloading_distance {
+            continue;
+        }
+        let Some(entity) = cm.chunks.get(&pos) else {
+            continue;
+        };
+        if let Ok(chunk) = <&Chunk>::query().get(world, *entity) {
+            chunk.load(entity, world, cm, store, matrix, cb);
+            paths.invalidate_chunk(pos);
+        }
     }
 }
 
@@ -115,14 +145,17 @@ pub fn load_chunk_bodies(
     world: &SubWorld,
     #[resource] cm: &ChunkManager,
     #[resource] pc: &mut PhysicsContext,
+    #[resource] jobs: &ChunkJobs,
     cb: &mut CommandBuffer
 ) {
-    let chunks_to_load: Vec<_> = <(Entity, &Chunk, &ChunkBody)>::query()
+    let mut chunks_to_load: Vec<_> = <(Entity, &Chunk, &ChunkBody)>::query()
         .iter(world)
         .filter(|(_, chunk, _)| chunk.pos.distance_squared(cm.player_chunk) < cm.unloading_distance)
         .filter(|(_, chunk, _)| chunk.state == ChunkState::Loaded)
         .filter(|(_, _, body)| body.state != ChunkState::Loaded)
         .collect();
+    chunks_to_load.sort_by_key(|(_, chunk, _)| chunk.pos.distance_squared(cm.player_chunk));
+    chunks_to_load.truncate(jobs.budget);
 
     for (entity, chunk, body) in chunks_to_load {
         body.load(entity, chunk, cm, pc, cb);
@@ -134,10 +167,13 @@ pub fn load_chunk_bodies(
 #[read_component(Monster)]
 #[read_component(ChunkBody)]
 #[read_component(crate::comps::WorldItem)]
+#[read_component(Fish)]
 pub fn unload_chunks(
     world: &SubWorld,
     #[resource] cm: &ChunkManager,
+    #[resource] store: &mut ChunkStore,
     #[resource] pc: &mut PhysicsContext,
+    #[resource] paths: &mut PathCache,
     cb: &mut CommandBuffer,
 ) {
     let chunks_to_unload: Vec<_> = <(Entity, &Chunk, &ChunkBody)>::query()
@@ -150,7 +186,9 @@ pub fn unload_chunks(
 
     for (entity, chunk, body) in chunks_to_unload {
         chunk.unload(entity, world, cb);
-        body.unload(entity, cm, pc, cb);
+        body.unload(entity, pc, cb);
+        paths.invalidate_chunk(chunk.pos);
+        store.flush(chunk.pos);
     }
 }
 
@@ -159,10 +197,19 @@ pub fn unload_chunks(
 #[read_component(Transform)]
 #[read_component(Monster)]
 #[read_component(ChunkBody)]
+#[read_component(Sprite)]
+#[read_component(Body)]
 #[read_component(crate::comps::WorldItem)]
+#[read_component(SpawnPoint)]
+#[read_component(Brain)]
+#[read_component(Loot)]
+#[read_component(Health)]
+#[read_component(Fish)]
+#[read_component(AirSource)]
 pub fn free_chunks(
     world: &SubWorld,
     #[resource] cm: &mut ChunkManager,
+    #[resource] store: &mut ChunkStore,
     #[resource] pc: &mut PhysicsContext,
     cb: &mut CommandBuffer,
 ) {
@@ -176,8 +223,56 @@ pub fn free_chunks(
 
     for (entity, chunk, body) in chunks_to_free {
         // Ensure physics are cleared if they were still loaded
-        body.unload(&entity, cm, pc, cb);
-        chunk.free(&entity, world, cb);
+        body.unload(&entity, pc, cb);
+        chunk.free(&entity, world, store, pc, cb);
         cm.chunks.remove(&chunk.pos);
     }
 }
+
+/// Applies the queued tile edits to every chunk that shares the tile, saves them in the
+/// store (so they survive unloading) and rebuilds the colliders of the edited chunks.
+#[system]
+#[write_component(Chunk)]
+#[write_component(ChunkBody)]
+pub fn apply_tile_edits(
+    world: &mut SubWorld,
+    #[resource] edits: &mut TileEdits,
+    #[resource] cm: &ChunkManager,
+    #[resource] store: &mut ChunkStore,
+    #[resource] pc: &mut PhysicsContext,
+    #[resource] paths: &mut PathCache,
+) {
+    let edits = edits.drain();
+    if edits.is_empty() {
+        return;
+    }
+
+    // The corner rows edited in each chunk
+    let mut dirty_chunks: HashMap<IVec2, RangeInclusive<usize>> = HashMap::new();
+    for edit in edits {
+        for (chunk_pos, local) in cm.chunks_of_tile(edit.tile) {
+            store.record_tile(chunk_pos, local.x, local.y, edit.value);
+            paths.invalidate_chunk(chunk_pos);
+
+            let Some(entity) = cm.chunks.get(&chunk_pos) else {
+                continue;
+            };
+            if let Ok(chunk) = <&mut Chunk>::query().get_mut(world, *entity)
+                && chunk.set_tile(local, edit.value)
+            {
+                let y = local.y as usize;
+                dirty_chunks
+                    .entry(chunk_pos)
+                    .and_modify(|rows| *rows = y.min(*rows.start())..=y.max(*rows.end()))
+                    .or_insert(y..=y);
+            }
+        }
+    }
+
+    for (chunk_pos, rows) in dirty_chunks {
+        let entity = cm.chunks[&chunk_pos];
+        if let Ok((chunk, body)) = <(&Chunk, &mut ChunkBody)>::query().get_mut(world, entity) {
+            body.rebuild(chunk, rows, cm, pc);
+        }
+    }
+}
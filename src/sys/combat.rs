@@ -0,0 +1,146 @@
+use legion::{Entity, query::*, system, systems::CommandBuffer, world::SubWorld};
+use macroquad::rand::gen_range;
+
+use crate::{
+    comps::*,
+    resources::{
+        Time,
+        chunk_manager::ChunkManager,
+        combat::{DeathEvent, Deaths, GameState},
+        inventory::ItemDef,
+        physics::PhysicsContext,
+        physics_events::PhysicsEvents,
+    },
+    sys::chunk::get_chunk_by_position,
+};
+
+fn remove_body(body: Option<&Body>, pc: &mut PhysicsContext) {
+    if let Some(handle) = body.and_then(|b| b.body_handle) {
+        pc.bodies.remove(
+            handle,
+            &mut pc.islands,
+            &mut pc.colliders,
+            &mut pc.impulse_joints,
+            &mut pc.multibody_joints,
+            true,
+        );
+    }
+}
+
+/// Bodies with `Damage` hurt the bodies with `Health` of another faction they touch
+/// (see `PhysicsEvents::touching`).
+/// Projectiles are removed on their first hit.
+#[system]
+#[read_component(Body)]
+#[read_component(Damage)]
+#[write_component(Health)]
+pub fn contact_damage(
+    world: &mut SubWorld,
+    #[resource] pc: &mut PhysicsContext,
+    #[resource] events: &PhysicsEvents,
+    #[resource] time: &Time,
+    cb: &mut CommandBuffer,
+) {
+    for health in <&mut Health>::query().iter_mut(world) {
+        health.invulnerable = (health.invulnerable - time.delta).max(0.0);
+    }
+
+    let mut touches = Vec::new();
+    for pair in events.touching() {
+        if let (Some(a), Some(b)) = pair.entities {
+            touches.push((a, b));
+            touches.push((b, a));
+        }
+    }
+
+    let mut spent = Vec::new();
+    for (source, target) in touches {
+        let Ok(damage) = <&Damage>::query().get(world, source).cloned() else {
+            continue;
+        };
+        let Ok(health) = <&mut Health>::query().get_mut(world, target) else {
+            continue;
+        };
+        if health.faction == damage.faction {
+            continue;
+        }
+        health.hurt(damage.amount);
+        if damage.consumed_on_hit && !spent.contains(&source) {
+            spent.push(source);
+        }
+    }
+
+    for entity in spent {
+        let body = <&Body>::query().get(world, entity).ok();
+        remove_body(body, pc);
+        cb.remove(entity);
+    }
+}
+
+/// Despawns the dead, dropping their loot, and records the deaths of the frame. The
+/// player isn't despawned: its death ends the game.
+#[system]
+#[read_component(Health)]
+#[read_component(Transform)]
+#[read_component(Body)]
+#[read_component(Loot)]
+#[read_component(Player)]
+pub fn handle_deaths(
+    world: &SubWorld,
+    #[resource] pc: &mut PhysicsContext,
+    #[resource] cm: &ChunkManager,
+    #[resource] deaths: &mut Deaths,
+    #[resource] state: &mut GameState,
+    cb: &mut CommandBuffer,
+) {
+    deaths.events.clear();
+
+    let mut q = <(
+        Entity,
+        &Health,
+        &Transform,
+        Option<&Body>,
+        Option<&Loot>,
+        Option<&Player>,
+    )>::query();
+    for (entity, health, transform, body, loot, player) in q.iter(world) {
+        if !health.is_dead() {
+            continue;
+        }
+        let event = DeathEvent {
+            entity: *entity,
+            position: transform.position,
+            faction: health.faction,
+        };
+
+        if player.is_some() {
+            if !state.game_over {
+                state.game_over = true;
+                deaths.events.push(event);
+            }
+            continue;
+        }
+        deaths.events.push(event);
+
+        for drop in loot.map(|l| l.drops.as_slice()).unwrap_or_default() {
+            if gen_range(0.0, 1.0) >= drop.chance {
+                continue;
+            }
+            match ItemDef::by_name(&drop.item) {
+                Some(def) => {
+                    WorldItem::push(
+                        cb,
+                        transform.position,
+                        def,
+                        drop.quantity,
+                        drop.image_path.clone(),
+                        get_chunk_by_position(transform.position, cm),
+                    );
+                }
+                None => eprintln!("Item desconhecido no saque: {}", drop.item),
+            }
+        }
+        remove_body(body, pc);
+        cb.remove(*entity);
+    }
+}
@@ -0,0 +1,146 @@
+use legion::{world::SubWorld, *};
+use macroquad::math::Vec2;
+use nalgebra::{point, vector};
+use rapier2d::prelude::{QueryFilter, Ray};
+
+use crate::{
+    comps::{Fish, Monster, Player, Transform},
+    resources::{
+        Time,
+        chunk_manager::ChunkManager,
+        flock::{FishSnapshot, FlockGrid, FlockParams},
+        physics::PhysicsContext,
+    },
+    sys::chunk::get_chunk_by_position,
+};
+
+/// Takes the snapshot the fish flock with this frame.
+#[system]
+#[read_component(Transform)]
+#[read_component(Fish)]
+#[read_component(Player)]
+#[read_component(Monster)]
+pub fn build_flock_grid(
+    world: &SubWorld,
+    #[resource] grid: &mut FlockGrid,
+    #[resource] params: &FlockParams,
+) {
+    grid.clear(params.view_radius);
+    for (t, fish) in <(&Transform, &Fish)>::query().iter(world) {
+        if fish.active {
+            grid.insert(FishSnapshot {
+                position: t.position,
+                velocity: fish.velocity,
+                school: fish.school,
+            });
+        }
+    }
+
+    for (t, _) in <(&Transform, &Player)>::query().iter(world) {
+        grid.threats.push(t.position);
+    }
+    for (t, monster) in <(&Transform, &Monster)>::query().iter(world) {
+        if monster.active {
+            grid.threats.push(t.position);
+        }
+    }
+}
+
+/// Boids: separation, alignment and cohesion within the school, fleeing from threats and
+/// steering away from the rock (the fixed colliders) ahead. A fish never moves through
+/// a collider; it bounces off it.
+#[system(par_for_each)]
+pub fn swim(
+    #[resource] grid: &FlockGrid,
+    #[resource] params: &FlockParams,
+    #[resource] pc: &PhysicsContext,
+    #[resource] time: &Time,
+    fish: &mut Fish,
+    transform: &mut Transform,
+) {
+    if !fish.active {
+        return;
+    }
+    let pos = transform.position;
+    let dt = time.delta;
+
+    let mut separation = Vec2::ZERO;
+    let mut heading = Vec2::ZERO;
+    let mut center = Vec2::ZERO;
+    let mut mates = 0;
+    for other in grid.near(pos) {
+        let offset = pos - other.position;
+        let distance = offset.length();
+        if distance == 0.0 || distance > params.view_radius {
+            continue;
+        }
+        if distance < params.separation_radius {
+            separation += offset / (distance * distance);
+        }
+        if other.school == fish.school {
+            heading += other.velocity;
+            center += other.position;
+            mates += 1;
+        }
+    }
+
+    let mut steer = separation.clamp_length_max(1.0) * params.separation;
+    if mates > 0 {
+        let mates = mates as f32;
+        steer += (heading / mates - fish.velocity) * params.alignment;
+        steer += (center / mates - pos) * params.cohesion;
+    }
+    for threat in &grid.threats {
+        let offset = pos - *threat;
+        let distance = offset.length();
+        if distance < params.threat_radius {
+            steer +=
+                offset.normalize_or_zero() * (1.0 - distance / params.threat_radius) * params.flee;
+        }
+    }
+
+    let filter = QueryFilter::only_fixed();
+    let direction = fish.velocity.normalize_or_zero();
+    let ray = Ray::new(point![pos.x, pos.y], vector![direction.x, direction.y]);
+    if let Some((_, hit)) = pc.query_pipeline.cast_ray_and_get_normal(
+        &pc.bodies,
+        &pc.colliders,
+        &ray,
+        params.look_ahead,
+        false,
+        filter,
+    ) {
+        let normal = Vec2::new(hit.normal.x, hit.normal.y);
+        steer += normal * (1.0 - hit.time_of_impact / params.look_ahead) * params.avoidance;
+    }
+
+    let velocity = fish.velocity + steer.clamp_length_max(params.max_acceleration) * dt;
+    let speed = velocity.length().clamp(params.min_speed, params.max_speed);
+    fish.velocity = velocity.normalize_or(direction) * speed;
+
+    let step = fish.velocity * dt;
+    let direction = fish.velocity.normalize_or_zero();
+    let ray = Ray::new(point![pos.x, pos.y], vector![direction.x, direction.y]);
+    match pc.query_pipeline.cast_ray_and_get_normal(
+        &pc.bodies,
+        &pc.colliders,
+        &ray,
+        step.length(),
+        false,
+        filter,
+    ) {
+        Some((_, hit)) => {
+            let normal = Vec2::new(hit.normal.x, hit.normal.y);
+            fish.velocity -= 2.0 * fish.velocity.dot(normal) * normal;
+        }
+        None => transform.position += step,
+    }
+    transform.rotation = fish.velocity.to_angle();
+}
+
+#[system(for_each)]
+pub fn update_fish_chunk(t: &Transform, f: &mut Fish, #[resource] cm: &ChunkManager) {
+    if f.active {
+        f.chunk = get_chunk_by_position(t.position, cm)
+    }
+}
@@ -1,13 +1,24 @@
 use egui_macroquad::egui::{self, Ui, ahash::{HashMap, HashMapExt}};
-use macroquad::math::Vec2;
-use macroquad::math::IVec2;
+use macroquad::math::{Vec2, vec2};
+use macroquad::{
+    color::{Color, GRAY, RED, WHITE, YELLOW},
+    miniquad::window::clipboard_set,
+    shapes::{draw_rectangle, draw_rectangle_lines},
+    text::{draw_text, measure_text},
+    texture::{DrawTextureParams, draw_texture_ex},
+    window::{screen_height, screen_width},
+};
 use legion::{world::SubWorld, systems::CommandBuffer, *};
 use crate::resources::inventory::ItemContext;
+use crate::sys::chunk::get_chunk_by_position;
 use crate::{
     comps::*,
     resources::{
+        Textures, Track,
+        chunk_manager::ChunkManager,
+        combat::GameState,
         gui_commands::{GuiCommand, GuiCommandBuffer},
-        input::InputContext,
+        input::{InputAction, InputContext},
     },
 };
 
@@ -45,6 +56,28 @@ pub fn load_windows(
     }
 }
 
+/// Shows the world seed, so a cave can be reported and regenerated with `--seed`, and
+/// the biome the player is in.
+#[system]
+pub fn draw_seed(
+    #[resource] cm: &ChunkManager,
+    #[resource] track: &Track,
+    #[resource] input: &mut InputContext,
+) {
+    if input.consume_action(InputAction::CopySeed) {
+        clipboard_set(&cm.seed.to_string());
+    }
+
+    let biome = cm.biomes.dominant(track.pos);
+    draw_text(
+        format!("Seed: {} (F2 copia)   Bioma: {}", cm.seed, biome.name).as_str(),
+        4.,
+        screen_height() - 8.,
+        20.,
+        WHITE,
+    );
+}
+
 #[system]
 pub fn render_egui(
 ) {
@@ -57,6 +90,7 @@ pub fn render_egui(
 pub fn process_gui_commands(
     world: &mut SubWorld,
     #[resource] gui_cmds: &mut GuiCommandBuffer,
+    #[resource] cm: &ChunkManager,
     cb: &mut CommandBuffer,
 ) {
     let commands = gui_cmds.drain();
@@ -78,29 +112,8 @@ pub fn process_gui_commands(
             for (item, quantity, spawn_pos) in drops {
                 let _ = holder.inventory.remove_item(item.clone(), quantity);
 
-                let chunk = IVec2::new(
-                    (spawn_pos.x / 16.0).floor() as i32,
-                    (spawn_pos.y / 16.0).floor() as i32,
-                );
-                cb.push((
-                    crate::comps::Transform {
-                        position: spawn_pos,
-                        ..Default::default()
-                    },
-                    crate::comps::Body::new(Vec2::new(0.1, 0.1), true),
-                    crate::comps::Sprite {
-                        image_path: "assets/gun.png".into(),
-                        z_order: 1.0,
-                        flip_x: false,
-                        flip_y: false,
-                    },
-                    crate::comps::WorldItem {
-                        item,
-                        quantity,
-                        chunk,
-                        active: true,
-                    },
-                ));
+                let chunk = get_chunk_by_position(spawn_pos, cm);
+                WorldItem::push(cb, spawn_pos, item, quantity, "assets/gun.png".into(), chunk);
             }
         }
     }
@@ -128,3 +141,82 @@ pub fn process_gui_commands(
         }
     }
 }
+
+/// The player's health, in the top right corner.
+#[system(for_each)]
+pub fn draw_health(health: &Health, _: &Player) {
+    let text = format!("Vida: {:.0}/{:.0}", health.current, health.max);
+    let size = measure_text(&text, None, 24, 1.0);
+    draw_text(&text, screen_width() - size.width - 8., 24., 24., WHITE);
+}
+
+/// The air left, under the health; red when running out.
+#[system(for_each)]
+pub fn draw_oxygen(oxygen: &Oxygen, _: &Player) {
+    let text = format!("Oxigênio: {:.0}/{:.0}", oxygen.current, oxygen.capacity);
+    let color = if oxygen.current < oxygen.capacity * 0.25 {
+        RED
+    } else {
+        WHITE
+    };
+    let size = measure_text(&text, None, 24, 1.0);
+    draw_text(&text, screen_width() - size.width - 8., 48., 24., color);
+}
+
+/// The loadout along the bottom, the weapon in hand framed, with the key of each slot.
+#[system(for_each)]
+pub fn draw_weapon_slots(holder: &WeaponHolder, _: &Player, #[resource] textures: &Textures) {
+    const SLOT: f32 = 48.;
+    const GAP: f32 = 6.;
+
+    let count = holder.loadout.len() as f32;
+    let left = (screen_width() - count * SLOT - (count - 1.) * GAP) / 2.;
+    let y = screen_height() - SLOT - 32.;
+    for (i, weapon) in holder.loadout.iter().enumerate() {
+        let x = left + i as f32 * (SLOT + GAP);
+        draw_rectangle(x, y, SLOT, SLOT, Color::new(0., 0., 0., 0.5));
+        if let Some(texture) = textures.0.get(&weapon.image_path()) {
+            let size = vec2(texture.width(), texture.height());
+            let size = size * (SLOT - 8.) / size.max_element();
+            draw_texture_ex(
+                texture,
+                x + (SLOT - size.x) / 2.,
+                y + (SLOT - size.y) / 2.,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(size),
+                    ..Default::default()
+                },
+            );
+        }
+        let (thickness, color) = if i == holder.slot {
+            (3., YELLOW)
+        } else {
+            (1., GRAY)
+        };
+        draw_rectangle_lines(x, y, SLOT, SLOT, thickness, color);
+        draw_text(&(i + 1).to_string(), x + 4., y + 14., 16., WHITE);
+    }
+}
+
+/// Tells the player they died and how to start over.
+#[system]
+pub fn draw_game_over(#[resource] state: &GameState) {
+    if !state.game_over {
+        return;
+    }
+    draw_rectangle(0., 0., screen_width(), screen_height(), Color::new(0., 0., 0., 0.6));
+    for (text, size, y) in [
+        ("Você morreu", 48, -20.),
+        ("Enter para recomeçar", 24, 24.),
+    ] {
+        let dims = measure_text(text, None, size, 1.0);
+        draw_text(
+            text,
+            (screen_width() - dims.width) / 2.,
+            screen_height() / 2. + y,
+            size as f32,
+            WHITE,
+        );
+    }
+}
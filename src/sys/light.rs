@@ -0,0 +1,212 @@
+use std::f32::consts::TAU;
+
+use legion::{world::SubWorld, *};
+use macroquad::{
+    miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams},
+    models::{Mesh, Vertex, draw_mesh},
+    prelude::*,
+};
+use nalgebra::{point, vector};
+use rapier2d::prelude::{QueryFilter, Ray};
+
+use super::render::camera_view_rect;
+use crate::{
+    comps::{LightShape, LightSource, Transform},
+    resources::{
+        METERS_TO_PIXELS, chunk_manager::ChunkManager, lighting::Lighting, physics::PhysicsContext,
+    },
+};
+
+const VERTEX: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}"#;
+
+const FRAGMENT: &str = r#"#version 100
+varying lowp vec4 color;
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+
+void main() {
+    gl_FragColor = color * texture2D(Texture, uv);
+}"#;
+
+/// How bright each screen pixel is, drawn every frame and multiplied over the scene.
+/// Made by `draw_lighting_system` on its first run, once there is a GL context.
+pub struct LightMap {
+    target: RenderTarget,
+    size: Vec2,
+    /// Lights add up on the map.
+    add: Material,
+    /// The map darkens what is under it.
+    multiply: Material,
+}
+
+impl LightMap {
+    fn new(size: Vec2) -> Self {
+        Self {
+            target: render_target(size.x as u32, size.y as u32),
+            size,
+            add: blend_material(BlendState::new(
+                Equation::Add,
+                BlendFactor::Value(BlendValue::SourceAlpha),
+                BlendFactor::One,
+            )),
+            multiply: blend_material(BlendState::new(
+                Equation::Add,
+                BlendFactor::Value(BlendValue::DestinationColor),
+                BlendFactor::Zero,
+            )),
+        }
+    }
+
+    /// Remakes the target when the window is resized.
+    fn fit(&mut self, size: Vec2) {
+        if self.size != size {
+            self.target = render_target(size.x as u32, size.y as u32);
+            self.size = size;
+        }
+    }
+}
+
+fn blend_material(blend: BlendState) -> Material {
+    load_material(
+        ShaderSource::Glsl {
+            vertex: VERTEX,
+            fragment: FRAGMENT,
+        },
+        MaterialParams {
+            pipeline_params: PipelineParams {
+                color_blend: Some(blend),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .expect("Erro ao compilar o material da iluminação")
+}
+
+/// Darkens the scene by depth and lights it back around every `LightSource` in view,
+/// each clipped by the walls to its visibility polygon. Must run after `render_system`,
+/// with the world camera set.
+#[system]
+#[read_component(Transform)]
+#[read_component(LightSource)]
+pub fn draw_lighting(
+    world: &SubWorld,
+    #[state] light_map: &mut Option<LightMap>,
+    #[resource] lighting: &Lighting,
+    #[resource] camera: &mut Box<Camera2D>,
+    #[resource] cm: &ChunkManager,
+    #[resource] pc: &PhysicsContext,
+) {
+    let screen = vec2(screen_width(), screen_height());
+    let map = light_map.get_or_insert_with(|| LightMap::new(screen));
+    map.fit(screen);
+
+    let depth = camera.target.y / METERS_TO_PIXELS / cm.chunk_size_in_meters.y;
+    let view = camera_view_rect(camera);
+
+    set_camera(&Camera2D {
+        target: camera.target,
+        zoom: camera.zoom,
+        render_target: Some(map.target.clone()),
+        ..Default::default()
+    });
+    clear_background(lighting.ambient(depth));
+    gl_use_material(&map.add);
+    for (transform, light) in <(&Transform, &LightSource)>::query().iter(world) {
+        let reach = light.radius * METERS_TO_PIXELS;
+        let center = transform.position * METERS_TO_PIXELS;
+        if !Rect::new(center.x - reach, center.y - reach, reach * 2., reach * 2.).overlaps(&view) {
+            continue;
+        }
+        let polygon = visibility_polygon(pc, lighting.rays, transform, light);
+        draw_mesh(&light_mesh(transform.position, light, &polygon));
+    }
+
+    // Back on the world camera, over what it sees
+    set_camera(camera.as_ref());
+    let min = camera.screen_to_world(Vec2::ZERO);
+    let max = camera.screen_to_world(screen);
+    gl_use_material(&map.multiply);
+    draw_texture_ex(
+        &map.target.texture,
+        min.x,
+        min.y,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(max - min),
+            ..Default::default()
+        },
+    );
+    gl_use_default_material();
+}
+
+//=====PRIVATE======
+
+/// The ends of rays cast from the light across its shape, each stopped by the first
+/// wall or at `radius`. Evenly spread, `rays` of them per full turn.
+fn visibility_polygon(
+    pc: &PhysicsContext,
+    rays: usize,
+    transform: &Transform,
+    light: &LightSource,
+) -> Vec<Vec2> {
+    let (start, span) = match light.shape {
+        LightShape::Point => (0.0, TAU),
+        LightShape::Cone { angle } => (transform.rotation - angle / 2., angle),
+    };
+    let count = ((rays as f32 * span / TAU).ceil() as usize).max(2);
+    let filter = QueryFilter::only_fixed().exclude_sensors();
+    let pos = transform.position;
+
+    (0..=count)
+        .map(|i| {
+            let direction = Vec2::from_angle(start + span * i as f32 / count as f32);
+            let ray = Ray::new(point![pos.x, pos.y], vector![direction.x, direction.y]);
+            let distance = pc
+                .query_pipeline
+                .cast_ray(&pc.bodies, &pc.colliders, &ray, light.radius, true, filter)
+                .map_or(light.radius, |(_, toi)| toi);
+            pos + direction * distance
+        })
+        .collect()
+}
+
+/// A fan from the light to its polygon, fading with the distance.
+fn light_mesh(center: Vec2, light: &LightSource, polygon: &[Vec2]) -> Mesh {
+    let vertex = |point: Vec2| {
+        let fade = 1.0 - point.distance(center) / light.radius;
+        let color = Color {
+            a: light.intensity * fade.max(0.0),
+            ..light.color
+        };
+        let point = point * METERS_TO_PIXELS;
+        Vertex::new(point.x, point.y, 0., 0., 0., color)
+    };
+
+    let mut vertices = vec![vertex(center)];
+    vertices.extend(polygon.iter().map(|point| vertex(*point)));
+    let indices = (1..polygon.len() as u16)
+        .flat_map(|i| [0, i, i + 1])
+        .collect();
+    Mesh {
+        vertices,
+        indices,
+        texture: None,
+    }
+}
@@ -0,0 +1,181 @@
+use std::{collections::HashMap, f32::consts::TAU};
+
+use legion::{world::SubWorld, *};
+use macroquad::prelude::*;
+
+use crate::{
+    comps::{Chunk, Player, Transform},
+    resources::{
+        Track,
+        chunk_manager::ChunkManager,
+        explored::{ExploredChunk, ExploredMap},
+        input::{InputAction, InputContext},
+        navigation::LoadedTiles,
+    },
+};
+
+/// Rays cast around the diver every frame to reveal what they see.
+const SIGHT_RAYS: usize = 180;
+
+const MINIMAP_SIZE: f32 = 180.;
+/// Pixels per meter of the minimap and of the full-screen map.
+const MINIMAP_SCALE: f32 = 12.;
+const MAP_SCALE: f32 = 6.;
+/// How close, in meters, a new marker can be to another before it removes it instead.
+const MARKER_RADIUS: f32 = 1.;
+
+const WATER: Color = Color::new(0.1, 0.25, 0.4, 1.0);
+const ROCK: Color = Color::new(0.6, 0.5, 0.38, 1.0);
+const BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 0.7);
+
+/// Reveals on the map the tiles the diver sees: rays walked over the loaded tiles, each
+/// stopped by the first rock or at `ExploredMap::sight`.
+#[system]
+#[read_component(Chunk)]
+#[read_component(Player)]
+#[read_component(Transform)]
+pub fn explore(
+    world: &SubWorld,
+    #[resource] explored: &mut ExploredMap,
+    #[resource] cm: &ChunkManager,
+) {
+    let tiles = LoadedTiles::new(<&Chunk>::query().iter(world), cm.chunk_size_in_tiles);
+    let step = cm.tile_size_in_meters.min_element();
+
+    for (transform, _) in <(&Transform, &Player)>::query().iter(world) {
+        for i in 0..SIGHT_RAYS {
+            let direction = Vec2::from_angle(TAU * i as f32 / SIGHT_RAYS as f32);
+            let mut distance = 0.;
+            while distance <= explored.sight {
+                // Tiles are corners, the nearest one is the closest to what is seen
+                let position = transform.position + direction * distance;
+                let tile = cm.world_to_tile(position);
+                let Some(water) = tiles.water(tile) else {
+                    break;
+                };
+                explored.reveal(tile, !water);
+                if !water {
+                    break;
+                }
+                distance += step;
+            }
+        }
+    }
+}
+
+/// The minimap in the bottom right corner, or the full-screen map while it's open.
+/// Both show the explored tiles around the diver and the markers.
+#[system]
+pub fn draw_map(
+    #[state] open: &mut bool,
+    #[state] textures: &mut HashMap<IVec2, (u32, Texture2D)>,
+    #[resource] explored: &mut ExploredMap,
+    #[resource] input: &mut InputContext,
+    #[resource] cm: &ChunkManager,
+    #[resource] track: &Track,
+) {
+    *open ^= input.consume_action(InputAction::MapToggle);
+    if input.consume_action(InputAction::MapMarker) {
+        explored.toggle_marker(track.pos, MARKER_RADIUS);
+    }
+
+    for (pos, chunk) in explored.chunks.iter() {
+        if textures
+            .get(pos)
+            .is_some_and(|(revision, _)| *revision == chunk.revision)
+        {
+            continue;
+        }
+        let texture = chunk_texture(chunk, cm.chunk_size_in_tiles);
+        textures.insert(*pos, (chunk.revision, texture));
+    }
+
+    let (view, scale) = if *open {
+        draw_rectangle(0., 0., screen_width(), screen_height(), BACKGROUND);
+        let margin = 32.;
+        let view = Rect::new(
+            margin,
+            margin,
+            screen_width() - margin * 2.,
+            screen_height() - margin * 2.,
+        );
+        (view, MAP_SCALE)
+    } else {
+        let view = Rect::new(
+            screen_width() - MINIMAP_SIZE - 8.,
+            screen_height() - MINIMAP_SIZE - 8.,
+            MINIMAP_SIZE,
+            MINIMAP_SIZE,
+        );
+        (view, MINIMAP_SCALE)
+    };
+    draw_rectangle(view.x, view.y, view.w, view.h, BACKGROUND);
+
+    let to_screen = |world_pos: Vec2| (world_pos - track.pos) * scale + view.center();
+    for (pos, (_, texture)) in textures.iter() {
+        let origin = to_screen(pos.as_vec2() * cm.chunk_size_in_meters);
+        let size = cm.chunk_size_in_meters * scale;
+        let dest = Rect::new(origin.x, origin.y, size.x, size.y);
+        let Some(clip) = dest.intersect(view) else {
+            continue;
+        };
+        // The part of the chunk inside the view, in texture pixels (tiles)
+        let tile = cm.tile_size_in_meters * scale;
+        let source = Rect::new(
+            (clip.x - dest.x) / tile.x,
+            (clip.y - dest.y) / tile.y,
+            clip.w / tile.x,
+            clip.h / tile.y,
+        );
+        draw_texture_ex(
+            texture,
+            clip.x,
+            clip.y,
+            WHITE,
+            DrawTextureParams {
+                source: Some(source),
+                dest_size: Some(clip.size()),
+                ..Default::default()
+            },
+        );
+    }
+
+    for marker in explored.markers.iter().map(|m| to_screen(*m)) {
+        if view.contains(marker) {
+            draw_circle(marker.x, marker.y, 4., RED);
+        }
+    }
+    let diver = view.center();
+    draw_circle(diver.x, diver.y, 3., YELLOW);
+    draw_rectangle_lines(view.x, view.y, view.w, view.h, 2., WHITE);
+
+    if *open {
+        draw_text(
+            "Mapa (M fecha, N marca ou desmarca)",
+            view.x,
+            view.y - 8.,
+            24.,
+            WHITE,
+        );
+    }
+}
+
+//=====PRIVATE======
+
+/// A pixel per tile: water, rock, or clear where it wasn't seen.
+fn chunk_texture(chunk: &ExploredChunk, size: UVec2) -> Texture2D {
+    let mut image = Image::gen_image_color(size.x as u16, size.y as u16, BLANK);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let tile = uvec2(x, y);
+            if !chunk.seen.get(tile) {
+                continue;
+            }
+            let color = if chunk.rock.get(tile) { ROCK } else { WATER };
+            image.set_pixel(x, y, color);
+        }
+    }
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
@@ -1,34 +1,59 @@
 use legion::Schedule;
 
+pub mod ai;
 pub mod chunk;
+pub mod combat;
+pub mod fish;
 pub mod player;
 pub mod hud;
 pub mod render;
 pub mod tick;
 pub mod weapons;
 pub mod inventory;
+pub mod light;
+pub mod map;
+pub mod oxygen;
 
 pub fn populate() -> (Schedule, Schedule) {
-    let step_schedule = Schedule::builder()
+    let mut step_builder = Schedule::builder();
+    step_builder
         .add_thread_local(tick::load_uninitialized_bodies_system())
         .add_thread_local(tick::input_update_system())
         .add_system(tick::step_animation_system(0.0))
         .add_system(render::z_y_axis_player_system())
+        .add_thread_local(ai::think_system())
+        .add_thread_local(tick::flow_currents_system())
         .add_thread_local(tick::step_physics_system())
         .add_thread_local(tick::integrate_physics_system())
+        .add_thread_local(combat::contact_damage_system())
+        .add_thread_local(oxygen::breathe_system())
+        .add_thread_local(combat::handle_deaths_system())
+        .add_system(fish::build_flock_grid_system())
+        .add_system(fish::swim_system())
         .add_thread_local(player::move_player_system())
         .add_thread_local(inventory::interact_pickup_system())
         .add_system(render::track_player_system())
         .add_thread_local(player::animate_player_system())
         .add_system(chunk::update_player_chunk_system())
         .add_system(chunk::update_monster_chunk_system())
+        .add_system(chunk::update_item_chunk_system())
+        .add_system(fish::update_fish_chunk_system())
         .add_system(chunk::create_new_chunks_system())
         .add_thread_local(chunk::load_chunks_system())
-        .add_thread_local(chunk::load_chunk_bodies_system())
+        .add_thread_local(chunk::load_chunk_bodies_system());
+    #[cfg(debug_assertions)]
+    step_builder.add_thread_local(player::debug_paint_tiles_system());
+    let step_schedule = step_builder
+        .add_thread_local(chunk::apply_tile_edits_system())
+        .add_system(map::explore_system())
         .add_thread_local(chunk::unload_chunks_system())
         .add_system(chunk::free_chunks_system())
+        .add_thread_local(weapons::switch_weapon_system())
+        .flush()
         .add_thread_local(weapons::init_weapons_system())
         .add_thread_local(weapons::shoot_system())
+        // The step sees what the shot changed, not the weapon of the frame before
+        .flush()
         .add_thread_local(weapons::step_system())
         .add_thread_local(weapons::bullet_spawn_system())
         .flush()
@@ -37,9 +62,11 @@ pub fn populate() -> (Schedule, Schedule) {
         .build();
 
     let draw_schedule = Schedule::builder()
+        .add_thread_local(render::bake_chunks_system())
         .add_thread_local(render::camera_system())
         .add_thread_local(render::clear_screen_system())
         .add_thread_local(render::render_system())
+        .add_thread_local(light::draw_lighting_system(None))
         .add_thread_local(inventory::inventory_window_system(
             false,
             std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
@@ -48,6 +75,15 @@ pub fn populate() -> (Schedule, Schedule) {
         .add_thread_local(render::camera_ui_system())
         .add_thread_local(tick::debug_input_system(false))
         .add_thread_local(render::draw_fps_system())
+        .add_thread_local(hud::draw_seed_system())
+        .add_thread_local(hud::draw_health_system())
+        .add_thread_local(hud::draw_oxygen_system())
+        .add_thread_local(hud::draw_weapon_slots_system())
+        .add_thread_local(map::draw_map_system(
+            false,
+            std::collections::HashMap::new(),
+        ))
+        .add_thread_local(hud::draw_game_over_system())
         .add_thread_local(hud::render_egui_system())
         .build();
 
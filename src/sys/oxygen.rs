@@ -0,0 +1,64 @@
+use legion::{world::SubWorld, *};
+
+use crate::{
+    comps::{AirSource, Health, Oxygen, Player, Transform},
+    resources::{
+        Time, chunk_manager::ChunkManager, combat::GameState, input::InputContext,
+        physics_events::PhysicsEvents,
+    },
+};
+
+/// Drains the diver's tank, by depth and by how hard they swim, and refills it inside
+/// air sources. With the tank empty the diver loses health until they die.
+#[system]
+#[read_component(Player)]
+#[read_component(Transform)]
+#[read_component(AirSource)]
+#[write_component(Oxygen)]
+#[write_component(Health)]
+pub fn breathe(
+    world: &mut SubWorld,
+    #[resource] events: &PhysicsEvents,
+    #[resource] input: &InputContext,
+    #[resource] cm: &ChunkManager,
+    #[resource] state: &GameState,
+    #[resource] time: &Time,
+) {
+    if state.game_over {
+        return;
+    }
+    let dt = time.delta;
+
+    let players: Vec<_> = <(Entity, &Transform, &Player)>::query()
+        .iter(world)
+        .map(|(e, t, _)| (*e, t.position))
+        .collect();
+    for (player, position) in players {
+        let refill: f32 = events
+            .touching()
+            .filter_map(|pair| match pair.entities {
+                (Some(a), Some(b)) if a == player => Some(b),
+                (Some(a), Some(b)) if b == player => Some(a),
+                _ => None,
+            })
+            .filter_map(|other| <&AirSource>::query().get(world, other).ok())
+            .map(|air| air.rate)
+            .sum();
+
+        let Ok((oxygen, health)) = <(&mut Oxygen, &mut Health)>::query().get_mut(world, player)
+        else {
+            continue;
+        };
+        if refill > 0.0 {
+            oxygen.refill(refill * dt);
+            continue;
+        }
+
+        let depth = position.y / cm.chunk_size_in_meters.y;
+        let exertion = input.move_direction.length();
+        oxygen.current = (oxygen.current - oxygen.drain_per_second(depth, exertion) * dt).max(0.0);
+        if oxygen.is_empty() {
+            health.lose(oxygen.suffocation_damage * dt);
+        }
+    }
+}
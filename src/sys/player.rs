@@ -7,25 +7,59 @@ use macroquad::{
 };
 use nalgebra::vector;
 
+#[cfg(debug_assertions)]
+use crate::resources::{
+    METERS_TO_PIXELS, chunk_manager::ChunkManager, input::InputAction, tile_edits::TileEdits,
+};
 use crate::{
-    comps::{AnimationPlayer, Body, Player, Sprite, Transform},
-    resources::{input::InputContext, physics::PhysicsContext},
+    comps::{AnimationPlayer, Body, Player, Transform},
+    resources::{combat::GameState, input::InputContext, physics::PhysicsContext},
 };
+#[cfg(debug_assertions)]
+use macroquad::{camera::Camera2D, input::mouse_position};
+
+#[cfg(debug_assertions)]
+const DEBUG_BRUSH_RADIUS: f32 = 0.3;
+
+/// Debug painting: digs a hole (right mouse) or places a rock tile (middle mouse) under
+/// the cursor. Only in debug builds.
+#[cfg(debug_assertions)]
+#[system]
+pub fn debug_paint_tiles(
+    #[resource] input: &mut InputContext,
+    #[resource] camera: &mut Box<Camera2D>,
+    #[resource] cm: &ChunkManager,
+    #[resource] edits: &mut TileEdits,
+) {
+    let cursor = camera.screen_to_world(mouse_position().into()) / METERS_TO_PIXELS;
+
+    if input.consume_action(InputAction::DebugDig) {
+        edits.fill_circle(cursor, DEBUG_BRUSH_RADIUS, 0, cm);
+    } else if input.consume_action(InputAction::DebugBuild) {
+        edits.set(cursor, 1, cm);
+    }
+}
 
 #[system(for_each)]
 pub fn move_player(
     #[resource] input_ctx: &mut InputContext,
     #[resource] physics_ctx: &mut PhysicsContext,
+    #[resource] state: &GameState,
     player: &Player,
     transform: &mut Transform,
-    sprite: &mut Sprite,
     body: &Body,
 ) {
     let bodies = &mut physics_ctx.bodies;
+    if state.game_over {
+        // The dead drift
+        return;
+    }
     if let Some(rb) = bodies.get_mut(body.body_handle.expect("Body não carregado")) {
         let dir = input_ctx.move_direction;
-        let velocity = dir * player.speed * get_frame_time();
-        rb.set_linvel(vector![velocity.x, velocity.y], true);
+        // A force, as the currents push (see `flow_currents`): against the drag, the
+        // swimming speed is the top speed, and the flow adds to it
+        let force = dir * player.speed * get_frame_time() * body.linear_drag * rb.mass();
+        rb.add_force(vector![force.x, force.y], true);
     }
     transform.rotation = input_ctx.look_direction.to_angle();
 }
@@ -2,8 +2,9 @@ use legion::{world::SubWorld, *};
 use macroquad::{color::*, math::*, prelude::*};
 
 use crate::{
+    common::autotile::marching_index,
     comps::{
-        AnimationPlayer, Body, Chunk, DebugSprite, Player, Sprite, Spritesheet, TileMap,
+        AnimationPlayer, Body, Chunk, ChunkBake, DebugSprite, Player, Sprite, Spritesheet, TileMap,
         TileMapSource, Transform, WeaponHolder,
     },
     resources::{renderable::Renderable, weapons::Bullet, *},
@@ -54,11 +55,96 @@ pub fn camera_ui() {
     set_default_camera();
 }
 
+/// Draws the tiles of the chunks whose matrix changed into their `ChunkBake`. Must run
+/// before `camera_system`, since it moves the camera to the render target.
+#[system(for_each)]
+pub fn bake_chunks(
+    chunk: &Chunk,
+    tilemap: &TileMap,
+    bake: &mut ChunkBake,
+    #[resource] textures: &Textures,
+) {
+    if bake.target.is_some() && bake.revision == chunk.revision {
+        return;
+    }
+    let Some(matrix) = &chunk.matrix else {
+        return;
+    };
+    let Some(tileset) = textures.0.get(&tilemap.tileset_path) else {
+        eprintln!("Tileset não carregada: {}", tilemap.tileset_path);
+        return;
+    };
+
+    // The matrix holds corners, the tiles are the cells between them
+    let cells = vec2((matrix.width - 1) as f32, (matrix.height - 1) as f32);
+    let size = cells * tilemap.tile_size_in_tileset;
+    let target = bake.target.take().unwrap_or_else(|| {
+        let target = render_target(size.x as u32, size.y as u32);
+        target.texture.set_filter(FilterMode::Nearest);
+        target
+    });
+
+    // y goes down, as in the world camera (render targets are flipped otherwise)
+    set_camera(&Camera2D {
+        target: size / 2.,
+        zoom: vec2(2. / size.x, 2. / size.y),
+        render_target: Some(target.clone()),
+        ..Default::default()
+    });
+    clear_background(BLANK);
+    for y in 0..matrix.height - 1 {
+        for x in 0..matrix.width - 1 {
+            let src = tilemap
+                .tiles
+                .get(&marching_index(matrix, x, y))
+                .expect("Algum tile não corresponde aos Tiles conhecidos");
+            let dst = vec2(x as f32, y as f32) * tilemap.tile_size_in_tileset;
+            draw_texture_ex(
+                tileset,
+                dst.x,
+                dst.y,
+                WHITE,
+                DrawTextureParams {
+                    source: Some(Rect::new(
+                        src.x as f32 * tilemap.tile_size_in_tileset.x,
+                        src.y as f32 * tilemap.tile_size_in_tileset.y,
+                        tilemap.tile_size_in_tileset.x,
+                        tilemap.tile_size_in_tileset.y,
+                    )),
+                    dest_size: Some(tilemap.tile_size_in_tileset),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    set_default_camera();
+
+    bake.target = Some(target);
+    bake.revision = chunk.revision;
+}
+
 #[system(for_each)]
 pub fn track_player(#[resource] track: &mut Track, _: &Player, t: &Transform) {
     track.pos = t.position;
 }
 
+/// How far out of the screen, in pixels, things are still drawn. Covers rotated sprites
+/// and the camera moving before the next frame.
+const CULLING_MARGIN: f32 = 64.0;
+
+/// What the camera sees, in world pixels, grown by `CULLING_MARGIN`.
+pub fn camera_view_rect(camera: &Camera2D) -> Rect {
+    let min = camera.screen_to_world(Vec2::ZERO);
+    let max = camera.screen_to_world(vec2(screen_width(), screen_height()));
+    let (min, max) = (min.min(max), min.max(max));
+    Rect::new(
+        min.x - CULLING_MARGIN,
+        min.y - CULLING_MARGIN,
+        max.x - min.x + CULLING_MARGIN * 2.,
+        max.y - min.y + CULLING_MARGIN * 2.,
+    )
+}
+
 #[system]
 #[read_component(Sprite)]
 #[read_component(Transform)]
@@ -68,10 +154,14 @@ pub fn track_player(#[resource] track: &mut Track, _: &Player, t: &Transform) {
 #[read_component(TileMap)]
 #[read_component(TileMapSource)]
 #[read_component(Body)]
-#[read_component(Chunk)]
+#[read_component(ChunkBake)]
 #[read_component(WeaponHolder)]
 #[read_component(Bullet)]
-pub fn render(world: &mut SubWorld, #[resource] textures: &Textures) {
+pub fn render(
+    world: &mut SubWorld,
+    #[resource] textures: &Textures,
+    #[resource] camera: &mut Box<Camera2D>,
+) {
     let mut renderables: Vec<(&Transform, &dyn Renderable)> = Vec::new();
 
     //Registering
@@ -100,7 +190,7 @@ pub fn render(world: &mut SubWorld, #[resource] textures: &Textures) {
         .iter()
         .for_each(|(t, r)| renderables.push((t, r)));
 
-    let chunk_storage = <(&Transform, &TileMap, &Chunk)>::query()
+    let chunk_storage = <(&Transform, &TileMap, &ChunkBake)>::query()
         .iter(world)
         .map(|(t, m, c)| (t, (m, c)))
         .collect::<Vec<_>>();
@@ -120,6 +210,13 @@ pub fn render(world: &mut SubWorld, #[resource] textures: &Textures) {
     <(&Transform, &Bullet)>::query()
         .iter(world)
         .for_each(|(t, r)| renderables.push((t, r)));
+    //Culling
+    let view = camera_view_rect(camera);
+    renderables.retain(|(t, r)| match r.bounds(t, textures) {
+        Some(bounds) => bounds.overlaps(&view),
+        None => true,
+    });
+
     //Sorting
     renderables.sort_by(|a, b| {
         let (_, x) = a;
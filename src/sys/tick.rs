@@ -1,4 +1,7 @@
-use legion::*;
+use std::collections::HashMap;
+
+use legion::{world::SubWorld, *};
+use nalgebra::vector;
 use macroquad::{
     color::*, text::draw_text,
 };
@@ -8,7 +11,9 @@ use crate::{
     resources::{
         Time,
         input::{InputAction, InputContext},
+        currents::Currents,
         physics::PhysicsContext,
+        physics_events::PhysicsEvents,
     },
 };
 
@@ -80,8 +85,28 @@ pub fn load_uninitialized_bodies(
     }
 }
 
+/// Steps the simulation, then publishes its collision events (see `PhysicsEvents`).
 #[system]
-pub fn step_physics(#[resource] p: &mut PhysicsContext) {
+#[read_component(Body)]
+#[read_component(ChunkBody)]
+pub fn step_physics(
+    world: &SubWorld,
+    #[resource] p: &mut PhysicsContext,
+    #[resource] events: &mut PhysicsEvents,
+) {
+    let mut owners = HashMap::new();
+    for (entity, body) in <(Entity, &Body)>::query().iter(world) {
+        if let Some(collider) = body.collider_handle {
+            owners.insert(collider, *entity);
+        }
+    }
+    for (entity, body) in <(Entity, &ChunkBody)>::query().iter(world) {
+        for (collider, _) in &body.colliders {
+            owners.insert(*collider, *entity);
+        }
+    }
+    events.set_owners(owners);
+
     p.pipeline.step(
         &p.gravity,
         &mut p.integration_parameters,
@@ -95,8 +120,38 @@ pub fn step_physics(#[resource] p: &mut PhysicsContext) {
         &mut p.ccd_solver,
         Some(&mut p.query_pipeline),
         &(),
-        &(),
+        events.collector(),
     );
+    events.collect();
+
+    // Forces last one step: whoever pushes adds theirs again each frame
+    for (_, rb) in p.bodies.iter_mut() {
+        rb.reset_forces(false);
+    }
+}
+
+/// Pushes the dynamic bodies along the water currents, with the force that makes the
+/// flow their top speed against their drag.
+#[system]
+#[read_component(Body)]
+#[read_component(Transform)]
+pub fn flow_currents(
+    world: &SubWorld,
+    #[resource] p: &mut PhysicsContext,
+    #[resource] currents: &mut Currents,
+    #[resource] time: &Time,
+) {
+    currents.advance(time.delta);
+    for (transform, body) in <(&Transform, &Body)>::query().iter(world) {
+        if !body.is_dynamic {
+            continue;
+        }
+        let Some(rb) = body.body_handle.and_then(|h| p.bodies.get_mut(h)) else {
+            continue;
+        };
+        let force = currents.sample(transform.position) * body.linear_drag * rb.mass();
+        rb.add_force(vector![force.x, force.y], true);
+    }
 }
 
 #[system(for_each)]
@@ -48,6 +48,77 @@ pub fn bullet_spawn(
     }
 }
 
+/// Takes another weapon of the loadout, by number key or mouse wheel. The one in hand
+/// `exit`s and goes back to its slot; `init_weapons_system` inits the new one.
+#[system]
+#[read_component(WeaponHolder)]
+#[read_component(Transform)]
+#[read_component(Body)]
+pub fn switch_weapon(
+    world: &SubWorld,
+    #[resource] pc: &mut PhysicsContext,
+    #[resource] ic: &mut InputContext,
+    cb: &mut CommandBuffer,
+) {
+    let player = <(Entity, &Body, &Transform, &WeaponHolder)>::query()
+        .iter(world)
+        .next();
+
+    let Some((e, b, t, w)) = player else {
+        return;
+    };
+    let count = w.loadout.len();
+    if count == 0 {
+        return;
+    }
+
+    let mut slot = w.slot;
+    for i in 0..count {
+        if ic.consume_action(InputAction::WeaponSlot(i)) {
+            slot = i;
+        }
+    }
+    if ic.consume_action(InputAction::WeaponNext) {
+        slot = (slot + 1) % count;
+    }
+    if ic.consume_action(InputAction::WeaponPrevious) {
+        slot = (slot + count - 1) % count;
+    }
+    if slot == w.slot {
+        return;
+    }
+
+    let mut loadout = w.loadout.clone();
+    if let Some(weapon) = w.weapon.as_ref() {
+        let w_t = get_weapon_transform(t);
+        let ctx = WeaponContext {
+            weapon_holder: w,
+            weapon_holder_entity: *e,
+            player_body: b,
+            world,
+            rotation: w_t.rotation,
+            position: w_t.position,
+            physics: Some(pc),
+        };
+        weapon.exit(cb, ctx);
+
+        let mut stowed = weapon.box_clone();
+        stowed.set_active(false);
+        loadout[w.slot] = stowed;
+    }
+    let mut weapon = loadout[slot].box_clone();
+    weapon.set_active(false);
+    // After what `exit` added, so this one stays
+    cb.add_component(
+        *e,
+        WeaponHolder {
+            weapon: Some(weapon),
+            loadout,
+            slot,
+        },
+    );
+}
+
 #[system]
 #[read_component(WeaponHolder)]
 #[read_component(Transform)]
@@ -118,6 +189,8 @@ pub fn shoot(
 #[read_component(WeaponHolder)]
 #[read_component(Transform)]
 #[read_component(Body)]
+#[read_component(Monster)]
+#[read_component(WorldItem)]
 pub fn step(
     world: &SubWorld,
     #[resource] pc: &mut PhysicsContext,